@@ -1,8 +1,13 @@
 pub mod relu;
 pub mod softmax;
+pub mod softmax_cross_entropy;
 
-use crate::tensor::Tensor;
+use crate::tensor::{ShapeError, Tensor};
 
-pub trait Activator<const BATCH_SIZE: usize, const N_INPUTS: usize> {
-    fn forward(self, inputs: &Tensor<f32, BATCH_SIZE, N_INPUTS>) -> Tensor<f32, BATCH_SIZE, N_INPUTS>;
+// Like layers, activators only know the number of inputs, the batch size is picked per call
+pub trait Activator<const N_INPUTS: usize> {
+    fn forward<const BATCH_SIZE: usize>(&mut self, inputs: &Tensor<f32, BATCH_SIZE, N_INPUTS>) -> Tensor<f32, BATCH_SIZE, N_INPUTS>;
+    // Takes the gradient of the loss w.r.t. the activation outputs and returns the gradient w.r.t. its inputs.
+    // Fails if the last forward pass didn't run with the same batch size
+    fn backward<const BATCH_SIZE: usize>(&mut self, dvalues: &Tensor<f32, BATCH_SIZE, N_INPUTS>) -> Result<Tensor<f32, BATCH_SIZE, N_INPUTS>, ShapeError>;
 }
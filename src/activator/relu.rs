@@ -1,9 +1,25 @@
-use crate::tensor::Tensor;
+use crate::tensor::{DynTensor, ShapeError, Tensor};
 
-pub struct ReLU<const BATCH_SIZE: usize, const N_INPUTS: usize>;
+pub struct ReLU<const N_INPUTS: usize> {
+    // Inputs of the last forward pass, gradients are masked where they were <= 0
+    inputs: DynTensor<f32>
+}
 
-impl<const N_INPUTS: usize, const BATCH_SIZE: usize> super::Activator<BATCH_SIZE, N_INPUTS> for ReLU<BATCH_SIZE, N_INPUTS> {
-    fn forward(self, inputs: &Tensor<f32, BATCH_SIZE, N_INPUTS>) -> Tensor<f32, BATCH_SIZE, N_INPUTS> {
+impl<const N_INPUTS: usize> ReLU<N_INPUTS> {
+    pub fn new() -> Self {
+        ReLU { inputs: DynTensor::new(0, N_INPUTS) }
+    }
+}
+
+impl<const N_INPUTS: usize> Default for ReLU<N_INPUTS> {
+    fn default() -> Self {
+        ReLU::new()
+    }
+}
+
+impl<const N_INPUTS: usize> super::Activator<N_INPUTS> for ReLU<N_INPUTS> {
+    fn forward<const BATCH_SIZE: usize>(&mut self, inputs: &Tensor<f32, BATCH_SIZE, N_INPUTS>) -> Tensor<f32, BATCH_SIZE, N_INPUTS> {
+        self.inputs = DynTensor::from(inputs);
         let mut res = inputs.clone();
         res.apply(|el| {
             match el < &0.0 {
@@ -13,4 +29,32 @@ impl<const N_INPUTS: usize, const BATCH_SIZE: usize> super::Activator<BATCH_SIZE
         });
         res
     }
-}
\ No newline at end of file
+
+    fn backward<const BATCH_SIZE: usize>(&mut self, dvalues: &Tensor<f32, BATCH_SIZE, N_INPUTS>) -> Result<Tensor<f32, BATCH_SIZE, N_INPUTS>, ShapeError> {
+        let inputs: Tensor<f32, BATCH_SIZE, N_INPUTS> = Tensor::try_from(&self.inputs)?;
+        let mut dinputs = dvalues.clone();
+        dinputs.apply_with(&inputs, |(dvalue, input)| {
+            match input <= &0.0 {
+                true => 0.0,
+                false => *dvalue
+            }
+        });
+        Ok(dinputs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activator::Activator;
+
+    #[test]
+    fn masks_gradients_where_inputs_are_not_positive() {
+        let mut relu = ReLU::<3>::new();
+        let inputs: Tensor<f32, 2, 3> = Tensor::from_data([[-1.0, 0.0, 2.0], [0.5, 1e-6, -3.0]]);
+        assert_eq!(relu.forward(&inputs), Tensor::from_data([[0.0, 0.0, 2.0], [0.5, 1e-6, 0.0]]));
+        // The gradient at exactly 0 is taken as 0
+        let dinputs = relu.backward(&Tensor::from_data([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]])).unwrap();
+        assert_eq!(dinputs, Tensor::from_data([[0.0, 0.0, 3.0], [4.0, 5.0, 0.0]]));
+    }
+}
@@ -1,17 +1,100 @@
-use crate::{activator::Activator, tensor::Tensor};
+use crate::tensor::{DynTensor, ShapeError, Tensor};
 use crate::tensor::Axis;
 
-const E: f32 = 2.71828182846;
+pub struct Softmax<const N_INPUTS: usize> {
+    // Outputs of the last forward pass, the Jacobian is built from them
+    output: DynTensor<f32>
+}
 
-pub struct Softmax<const BATCH_SIZE: usize, const N_INPUTS: usize>;
+impl<const N_INPUTS: usize> Softmax<N_INPUTS> {
+    pub fn new() -> Self {
+        Softmax { output: DynTensor::new(0, N_INPUTS) }
+    }
+
+    // Outputs of the last forward pass, fails if BATCH_SIZE is not the one it ran with
+    pub fn output<const BATCH_SIZE: usize>(&self) -> Result<Tensor<f32, BATCH_SIZE, N_INPUTS>, ShapeError> {
+        Tensor::try_from(&self.output)
+    }
+}
 
-impl<const N_INPUTS: usize, const BATCH_SIZE: usize> super::Activator<BATCH_SIZE, N_INPUTS> for Softmax<BATCH_SIZE, N_INPUTS>  {
-    fn forward(self, inputs: &Tensor<f32, BATCH_SIZE, N_INPUTS>) -> Tensor<f32, BATCH_SIZE, N_INPUTS> {
+impl<const N_INPUTS: usize> Default for Softmax<N_INPUTS> {
+    fn default() -> Self {
+        Softmax::new()
+    }
+}
+
+impl<const N_INPUTS: usize> super::Activator<N_INPUTS> for Softmax<N_INPUTS>  {
+    fn forward<const BATCH_SIZE: usize>(&mut self, inputs: &Tensor<f32, BATCH_SIZE, N_INPUTS>) -> Tensor<f32, BATCH_SIZE, N_INPUTS> {
         let mut res = inputs.clone();
         let max_col = res.max_axis(Axis::Row).unwrap_row();
         res = res - max_col.broadcast::<BATCH_SIZE, N_INPUTS>();
-        res.apply(|el| { E.powf(*el) });
+        res.apply(|el| { el.exp() });
         let exp_sum = res.sum_axis(Axis::Row).unwrap_row();
-        res / exp_sum.broadcast::<BATCH_SIZE, N_INPUTS>()
+        let output = res / exp_sum.broadcast::<BATCH_SIZE, N_INPUTS>();
+        self.output = DynTensor::from(&output);
+        output
+    }
+
+    fn backward<const BATCH_SIZE: usize>(&mut self, dvalues: &Tensor<f32, BATCH_SIZE, N_INPUTS>) -> Result<Tensor<f32, BATCH_SIZE, N_INPUTS>, ShapeError> {
+        let output = self.output::<BATCH_SIZE>()?;
+        let mut dinputs: Tensor<f32, BATCH_SIZE, N_INPUTS> = Tensor::new();
+        for sample in 0..BATCH_SIZE {
+            // Jacobian of the softmax for one sample: diag(s) - s * s^T
+            let mut jacobian: Tensor<f32, N_INPUTS, N_INPUTS> = Tensor::new();
+            for i in 0..N_INPUTS {
+                for j in 0..N_INPUTS {
+                    let s_i = output[(sample, i)];
+                    let s_j = output[(sample, j)];
+                    jacobian[(i, j)] = if i == j { s_i * (1.0 - s_j) } else { -s_i * s_j };
+                }
+            }
+            for i in 0..N_INPUTS {
+                let mut grad = 0.0;
+                for j in 0..N_INPUTS {
+                    grad += jacobian[(i, j)] * dvalues[(sample, j)];
+                }
+                dinputs[(sample, i)] = grad;
+            }
+        }
+        Ok(dinputs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activator::Activator;
+
+    // sum(softmax(inputs) * dvalues), whose gradient w.r.t. the inputs is what backward returns
+    fn weighted_output<const BATCH_SIZE: usize, const N_INPUTS: usize>(inputs: &Tensor<f32, BATCH_SIZE, N_INPUTS>, dvalues: &Tensor<f32, BATCH_SIZE, N_INPUTS>) -> f32 {
+        let output = Softmax::new().forward(inputs);
+        let mut sum = 0.0;
+        for i in 0..BATCH_SIZE {
+            for j in 0..N_INPUTS {
+                sum += output[(i, j)] * dvalues[(i, j)];
+            }
+        }
+        sum
+    }
+
+    #[test]
+    fn backward_matches_numeric_gradient() {
+        let inputs: Tensor<f32, 2, 4> = Tensor::from_data([[1.0, -0.5, 2.0, 0.0], [0.3, 0.3, -1.2, 2.5]]);
+        let dvalues: Tensor<f32, 2, 4> = Tensor::from_data([[0.5, -1.0, 2.0, 0.0], [1.0, 0.0, -0.3, 0.7]]);
+        let mut softmax = Softmax::new();
+        softmax.forward(&inputs);
+        let dinputs = softmax.backward(&dvalues).unwrap();
+
+        // Central differences
+        let h = 1e-2;
+        for i in 0..2 {
+            for j in 0..4 {
+                let (mut plus, mut minus) = (inputs.clone(), inputs.clone());
+                plus[(i, j)] += h;
+                minus[(i, j)] -= h;
+                let numeric = (weighted_output(&plus, &dvalues) - weighted_output(&minus, &dvalues)) / (2.0 * h);
+                assert!((dinputs[(i, j)] - numeric).abs() < 1e-3, "{} != {} at ({}, {})", dinputs[(i, j)], numeric, i, j);
+            }
+        }
     }
-}
\ No newline at end of file
+}
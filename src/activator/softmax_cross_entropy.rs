@@ -0,0 +1,75 @@
+use crate::activator::Activator;
+use crate::activator::softmax::Softmax;
+use crate::metrics::Targets;
+use crate::metrics::loss::{CrossEntropyLoss, Loss};
+use crate::tensor::{ShapeError, Tensor};
+
+// Softmax activation followed by cross-entropy loss. Combining both gives a much simpler
+// and numerically stabler gradient than chaining the softmax Jacobian with the loss gradient
+pub struct SoftmaxCrossEntropy<const N_INPUTS: usize> {
+    softmax: Softmax<N_INPUTS>,
+    loss: CrossEntropyLoss<N_INPUTS>
+}
+
+impl<const N_INPUTS: usize> SoftmaxCrossEntropy<N_INPUTS> {
+    pub fn new() -> Self {
+        SoftmaxCrossEntropy {
+            softmax: Softmax::new(),
+            loss: CrossEntropyLoss {}
+        }
+    }
+
+    // Softmax outputs of the last forward pass, fails if BATCH_SIZE is not the one it ran with
+    pub fn output<const BATCH_SIZE: usize>(&self) -> Result<Tensor<f32, BATCH_SIZE, N_INPUTS>, ShapeError> {
+        self.softmax.output()
+    }
+
+    // Returns the loss, the softmax outputs are kept and available through `output`
+    pub fn forward<const BATCH_SIZE: usize>(&mut self, inputs: &Tensor<f32, BATCH_SIZE, N_INPUTS>, targets: &Targets<BATCH_SIZE, N_INPUTS>) -> f32 {
+        let output = self.softmax.forward(inputs);
+        self.loss.forward(&output, targets)
+    }
+
+    // Gradient w.r.t. the softmax inputs: (predictions - onehot) / BATCH_SIZE
+    pub fn backward<const BATCH_SIZE: usize>(&mut self, targets: &Targets<BATCH_SIZE, N_INPUTS>) -> Result<Tensor<f32, BATCH_SIZE, N_INPUTS>, ShapeError> {
+        let mut dinputs = self.output::<BATCH_SIZE>()?;
+        let class_ids = targets.class_ids();
+        for i in 0..BATCH_SIZE {
+            dinputs[(i, class_ids[(i, 0)])] -= 1.0;
+        }
+        Ok(dinputs / BATCH_SIZE as f32)
+    }
+}
+
+impl<const N_INPUTS: usize> Default for SoftmaxCrossEntropy<N_INPUTS> {
+    fn default() -> Self {
+        SoftmaxCrossEntropy::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_chained_gradient() {
+        let inputs: Tensor<f32, 3, 3> = Tensor::from_data([[1.0, 2.0, 0.5], [-1.0, 0.0, 3.0], [0.2, 0.2, 0.2]]);
+        let categorical = Targets::categorical(Tensor::from_data([[1], [0], [2]]));
+        let onehot = Targets::onehot(categorical.to_onehot());
+        for targets in [categorical, onehot] {
+            let mut fused = SoftmaxCrossEntropy::new();
+            fused.forward(&inputs, &targets);
+            let dinputs = fused.backward(&targets).unwrap();
+
+            let mut softmax = Softmax::new();
+            let output = softmax.forward(&inputs);
+            let dloss = CrossEntropyLoss {}.backward(&output, &targets);
+            let chained = softmax.backward(&dloss).unwrap();
+            for i in 0..3 {
+                for j in 0..3 {
+                    assert!((dinputs[(i, j)] - chained[(i, j)]).abs() < 1e-5, "{:?} != {:?}", dinputs, chained);
+                }
+            }
+        }
+    }
+}
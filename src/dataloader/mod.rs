@@ -0,0 +1,142 @@
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+
+use crate::metrics::Targets;
+use crate::tensor::Tensor;
+
+// What to do with the samples left over when N_SAMPLES is not a multiple of the batch size
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LastBatch {
+    // Leave them out of this epoch
+    #[default]
+    Drop,
+    // Fill the last batch up with samples from the start of the epoch's order
+    Wrap
+}
+
+// Splits an in-memory dataset into mini-batches. The batch size is picked per call to `batches`
+// so the same loader can feed training and evaluation with different batch sizes.
+// The dataset is borrowed, only the batches are copied out of it
+pub struct DataLoader<'a, const N_SAMPLES: usize, const N_FEATURES: usize, const N_OUTPUTS: usize> {
+    features: &'a Tensor<f32, N_SAMPLES, N_FEATURES>,
+    targets: &'a Targets<N_SAMPLES, N_OUTPUTS>,
+    rng: Option<StdRng>,
+    last_batch: LastBatch
+}
+
+impl<'a, const N_SAMPLES: usize, const N_FEATURES: usize, const N_OUTPUTS: usize> DataLoader<'a, N_SAMPLES, N_FEATURES, N_OUTPUTS> {
+    // Samples are served in order without shuffling, a partial last batch is dropped
+    pub fn new(features: &'a Tensor<f32, N_SAMPLES, N_FEATURES>, targets: &'a Targets<N_SAMPLES, N_OUTPUTS>) -> Self {
+        DataLoader { features, targets, rng: None, last_batch: LastBatch::Drop }
+    }
+
+    // Shuffles the samples at the start of every epoch, the same seed gives the same sequence of epochs
+    pub fn shuffled(mut self, seed: u64) -> Self {
+        self.rng = Some(StdRng::seed_from_u64(seed));
+        self
+    }
+
+    pub fn last_batch(mut self, last_batch: LastBatch) -> Self {
+        self.last_batch = last_batch;
+        self
+    }
+
+    pub fn features(&self) -> &'a Tensor<f32, N_SAMPLES, N_FEATURES> {
+        self.features
+    }
+
+    pub fn targets(&self) -> &'a Targets<N_SAMPLES, N_OUTPUTS> {
+        self.targets
+    }
+
+    // Number of batches `batches::<BATCH_SIZE>` yields per epoch
+    pub fn n_batches<const BATCH_SIZE: usize>(&self) -> usize {
+        match self.last_batch {
+            LastBatch::Drop => N_SAMPLES / BATCH_SIZE,
+            LastBatch::Wrap => N_SAMPLES.div_ceil(BATCH_SIZE)
+        }
+    }
+
+    // One epoch over the dataset
+    pub fn batches<const BATCH_SIZE: usize>(&mut self) -> Batches<'_, BATCH_SIZE, N_SAMPLES, N_FEATURES, N_OUTPUTS> {
+        assert!(BATCH_SIZE > 0 && BATCH_SIZE <= N_SAMPLES, "Batch size must be in 1..={}", N_SAMPLES);
+        let mut order: Vec<usize> = (0..N_SAMPLES).collect();
+        if let Some(rng) = self.rng.as_mut() {
+            order.shuffle(rng);
+        }
+        let n_batches = self.n_batches::<BATCH_SIZE>();
+        Batches { loader: self, order, batch: 0, n_batches }
+    }
+}
+
+pub struct Batches<'a, const BATCH_SIZE: usize, const N_SAMPLES: usize, const N_FEATURES: usize, const N_OUTPUTS: usize> {
+    loader: &'a DataLoader<'a, N_SAMPLES, N_FEATURES, N_OUTPUTS>,
+    order: Vec<usize>,
+    batch: usize,
+    n_batches: usize
+}
+
+impl<const BATCH_SIZE: usize, const N_SAMPLES: usize, const N_FEATURES: usize, const N_OUTPUTS: usize> Iterator for Batches<'_, BATCH_SIZE, N_SAMPLES, N_FEATURES, N_OUTPUTS> {
+    type Item = (Tensor<f32, BATCH_SIZE, N_FEATURES>, Targets<BATCH_SIZE, N_OUTPUTS>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.batch >= self.n_batches {
+            return None
+        }
+        let start = self.batch * BATCH_SIZE;
+        let indices: Vec<usize> = (start..start + BATCH_SIZE).map(|i| self.order[i % N_SAMPLES]).collect();
+        self.batch += 1;
+        Some((self.loader.features.select_rows(&indices), self.loader.targets.select_rows(&indices)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.n_batches - self.batch;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<const BATCH_SIZE: usize, const N_SAMPLES: usize, const N_FEATURES: usize, const N_OUTPUTS: usize> ExactSizeIterator for Batches<'_, BATCH_SIZE, N_SAMPLES, N_FEATURES, N_OUTPUTS> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data() -> (Tensor<f32, 5, 1>, Targets<5, 2>) {
+        let features = Tensor::from_data([[0.0], [1.0], [2.0], [3.0], [4.0]]);
+        let targets = Targets::categorical(Tensor::from_data([[0], [1], [0], [1], [0]]));
+        (features, targets)
+    }
+
+    fn sample_ids<const B: usize>(loader: &mut DataLoader<5, 1, 2>) -> Vec<usize> {
+        loader.batches::<B>().flat_map(|(x, _)| (0..B).map(move |i| x[(i, 0)] as usize)).collect()
+    }
+
+    #[test]
+    fn last_batch_policy() {
+        let (features, targets) = data();
+        let mut drop = DataLoader::new(&features, &targets);
+        assert_eq!(drop.batches::<2>().len(), 2);
+        assert_eq!(sample_ids::<2>(&mut drop), vec![0, 1, 2, 3]);
+
+        let mut wrap = DataLoader::new(&features, &targets).last_batch(LastBatch::Wrap);
+        assert_eq!(sample_ids::<2>(&mut wrap), vec![0, 1, 2, 3, 4, 0]);
+    }
+
+    #[test]
+    fn seeded_shuffle() {
+        let (features, targets) = data();
+        let mut a = DataLoader::new(&features, &targets).shuffled(7);
+        let mut b = DataLoader::new(&features, &targets).shuffled(7);
+        let first = sample_ids::<5>(&mut a);
+        assert_eq!(first, sample_ids::<5>(&mut b));
+        let mut sorted = first.clone();
+        sorted.sort();
+        assert_eq!(sorted, vec![0, 1, 2, 3, 4]);
+
+        // targets travel with their features
+        for (x, y) in a.batches::<1>() {
+            assert_eq!(y.class_ids()[(0, 0)], x[(0, 0)] as usize % 2);
+        }
+    }
+}
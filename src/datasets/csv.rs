@@ -0,0 +1,241 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+use crate::metrics::Targets;
+use crate::tensor::{DynTensor, Tensor};
+
+// Reader for tabular data: every column except the label column is a numeric feature.
+// Labels are either class indices (0, 1, 2, ...) or class names, which are numbered in
+// alphabetical order unless the classes are given explicitly. Fields may be quoted with "
+
+#[derive(Debug)]
+pub enum CsvError {
+    Io(io::Error),
+    UnknownColumn(String),
+    // Line numbers start at 1 and count the header and empty lines
+    FieldCount { line: usize, expected: usize, found: usize },
+    InvalidNumber { line: usize, column: String, value: String },
+    InvalidLabel { line: usize, value: String },
+    TooManyClasses { found: Vec<String>, n_classes: usize },
+    RowCount { expected: usize, found: usize }
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsvError::Io(err) => write!(f, "csv: {}", err),
+            CsvError::UnknownColumn(column) => write!(f, "csv: no column {}", column),
+            CsvError::FieldCount { line, expected, found } => write!(f, "csv: line {}: expected {} fields but found {}", line, expected, found),
+            CsvError::InvalidNumber { line, column, value } => write!(f, "csv: line {}: column {}: {:?} is not a number", line, column, value),
+            CsvError::InvalidLabel { line, value } => write!(f, "csv: line {}: unknown class {:?}", line, value),
+            CsvError::TooManyClasses { found, n_classes } => write!(f, "csv: found {} classes {:?} but expected at most {}", found.len(), found, n_classes),
+            CsvError::RowCount { expected, found } => write!(f, "csv: expected {} rows but found {}", expected, found)
+        }
+    }
+}
+
+impl std::error::Error for CsvError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CsvError::Io(err) => Some(err),
+            _ => None
+        }
+    }
+}
+
+impl From<io::Error> for CsvError {
+    fn from(err: io::Error) -> Self {
+        CsvError::Io(err)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LabelColumn {
+    // 0 is the first column
+    Index(usize),
+    // Needs a header row
+    Name(String)
+}
+
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    label: LabelColumn,
+    header: bool,
+    delimiter: char,
+    classes: Option<Vec<String>>
+}
+
+impl CsvOptions {
+    // Comma separated with a header row
+    pub fn new(label: LabelColumn) -> Self {
+        CsvOptions { label, header: true, delimiter: ',', classes: None }
+    }
+
+    pub fn header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    // Fixes the class of every label to its position in `classes`, so that e.g. a train
+    // and a test file missing some classes get the same class indices
+    pub fn classes<S: Into<String>>(mut self, classes: impl IntoIterator<Item = S>) -> Self {
+        self.classes = Some(classes.into_iter().map(Into::into).collect());
+        self
+    }
+}
+
+pub struct CsvDataset<const N_SAMPLES: usize, const N_FEATURES: usize, const N_CLASSES: usize> {
+    pub features: Tensor<f32, N_SAMPLES, N_FEATURES>,
+    pub targets: Targets<N_SAMPLES, N_CLASSES>,
+    // Label of every class index
+    pub classes: Vec<String>
+}
+
+// Splits one line into fields, "a,""b"",c" gives a, "b", c
+fn split_fields(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            c if c == delimiter && !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c)
+        }
+    }
+    fields.push(field);
+    fields.iter().map(|field| field.trim().to_string()).collect()
+}
+
+pub fn read_csv<R: BufRead, const N_SAMPLES: usize, const N_FEATURES: usize, const N_CLASSES: usize>(reader: R, options: &CsvOptions) -> Result<CsvDataset<N_SAMPLES, N_FEATURES, N_CLASSES>, CsvError> {
+    let n_fields = N_FEATURES + 1;
+    let mut header: Option<Vec<String>> = None;
+    let mut label_col = match &options.label {
+        LabelColumn::Index(idx) if *idx < n_fields => Some(*idx),
+        LabelColumn::Index(idx) => return Err(CsvError::UnknownColumn(idx.to_string())),
+        LabelColumn::Name(_) => None
+    };
+    let mut features = Vec::with_capacity(N_SAMPLES * N_FEATURES);
+    let mut labels = Vec::with_capacity(N_SAMPLES);
+    for (i, line) in reader.lines().enumerate() {
+        let line_no = i + 1;
+        let line = line?;
+        if line.trim().is_empty() {
+            continue
+        }
+        let fields = split_fields(&line, options.delimiter);
+        if fields.len() != n_fields {
+            return Err(CsvError::FieldCount { line: line_no, expected: n_fields, found: fields.len() })
+        }
+        if options.header && header.is_none() {
+            if let LabelColumn::Name(name) = &options.label {
+                label_col = Some(fields.iter().position(|field| field == name).ok_or_else(|| CsvError::UnknownColumn(name.clone()))?);
+            }
+            header = Some(fields);
+            continue
+        }
+        let label_col = label_col.ok_or_else(|| match &options.label {
+            LabelColumn::Name(name) => CsvError::UnknownColumn(name.clone()),
+            LabelColumn::Index(idx) => CsvError::UnknownColumn(idx.to_string())
+        })?;
+        for (col, field) in fields.iter().enumerate() {
+            if col == label_col {
+                continue
+            }
+            let value = field.parse::<f32>().map_err(|_| CsvError::InvalidNumber {
+                line: line_no,
+                column: header.as_ref().map_or(col.to_string(), |header| header[col].clone()),
+                value: field.clone()
+            })?;
+            features.push(value);
+        }
+        labels.push((line_no, fields[label_col].clone()));
+    }
+    if labels.len() != N_SAMPLES {
+        return Err(CsvError::RowCount { expected: N_SAMPLES, found: labels.len() })
+    }
+
+    // Class indices are used as they are
+    let numeric = options.classes.is_none() && labels.iter().all(|(_, label)| label.parse::<usize>().is_ok());
+    let classes = match &options.classes {
+        Some(classes) => classes.clone(),
+        None if numeric => (0..N_CLASSES).map(|class| class.to_string()).collect(),
+        None => {
+            let mut classes: Vec<String> = labels.iter().map(|(_, label)| label.clone()).collect();
+            classes.sort();
+            classes.dedup();
+            classes
+        }
+    };
+    if classes.len() > N_CLASSES {
+        return Err(CsvError::TooManyClasses { found: classes, n_classes: N_CLASSES })
+    }
+    let mut class_ids: Tensor<usize, N_SAMPLES, 1> = Tensor::new();
+    for (i, (line, label)) in labels.into_iter().enumerate() {
+        let class = if numeric {
+            label.parse::<usize>().ok().filter(|&class| class < N_CLASSES)
+        } else {
+            classes.iter().position(|class| *class == label)
+        };
+        class_ids[(i, 0)] = class.ok_or(CsvError::InvalidLabel { line, value: label })?;
+    }
+
+    let features = DynTensor::from_vec(N_SAMPLES, N_FEATURES, features).expect("every row has N_FEATURES features");
+    Ok(CsvDataset {
+        features: Tensor::try_from(features).expect("rows were checked against the tensor shape"),
+        targets: Targets::categorical(class_ids),
+        classes
+    })
+}
+
+// e.g. load_csv::<150, 4, 3>("iris.csv", &CsvOptions::new(LabelColumn::Name("species".into())))
+pub fn load_csv<const N_SAMPLES: usize, const N_FEATURES: usize, const N_CLASSES: usize>(path: impl AsRef<Path>, options: &CsvOptions) -> Result<CsvDataset<N_SAMPLES, N_FEATURES, N_CLASSES>, CsvError> {
+    read_csv(BufReader::new(File::open(path)?), options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IRIS: &str = "sepal,petal,species\n5.1,1.4,setosa\n\n6.3,4.9,\"versicolor\"\n5.0,1.3,setosa\n";
+
+    #[test]
+    fn string_and_numeric_labels() {
+        let options = CsvOptions::new(LabelColumn::Name("species".into()));
+        let data: CsvDataset<3, 2, 2> = read_csv(IRIS.as_bytes(), &options).unwrap();
+        assert_eq!(data.features, Tensor::from_data([[5.1, 1.4], [6.3, 4.9], [5.0, 1.3]]));
+        assert_eq!(data.targets.class_ids(), Tensor::from_data([[0], [1], [0]]));
+        assert_eq!(data.classes, vec!["setosa", "versicolor"]);
+
+        let options = CsvOptions::new(LabelColumn::Index(0)).header(false).delimiter(';');
+        let data: CsvDataset<2, 1, 3> = read_csv("2;0.5\n00;1.5\n".as_bytes(), &options).unwrap();
+        assert_eq!(data.targets.class_ids(), Tensor::from_data([[2], [0]]));
+    }
+
+    #[test]
+    fn errors_report_line_numbers() {
+        let options = CsvOptions::new(LabelColumn::Name("species".into()));
+        let err = read_csv::<_, 3, 2, 2>("sepal,petal,species\n5.1,x,setosa\n".as_bytes(), &options).err().unwrap();
+        assert_eq!(err.to_string(), "csv: line 2: column petal: \"x\" is not a number");
+        let err = read_csv::<_, 3, 2, 2>("sepal,petal,species\n5.1,1.4,setosa\n\n1.0,2.0\n".as_bytes(), &options).err().unwrap();
+        assert!(matches!(err, CsvError::FieldCount { line: 4, expected: 3, found: 2 }));
+        let err = read_csv::<_, 3, 2, 1>(IRIS.as_bytes(), &options).err().unwrap();
+        assert!(matches!(err, CsvError::TooManyClasses { .. }));
+        let err = read_csv::<_, 3, 2, 2>(IRIS.as_bytes(), &options.clone().classes(["setosa", "virginica"])).err().unwrap();
+        assert!(matches!(err, CsvError::InvalidLabel { line: 4, .. }));
+        let err = read_csv::<_, 3, 2, 2>(IRIS.as_bytes(), &CsvOptions::new(LabelColumn::Name("class".into()))).err().unwrap();
+        assert!(matches!(err, CsvError::UnknownColumn(_)));
+    }
+}
@@ -0,0 +1,223 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+
+use crate::metrics::Targets;
+use crate::tensor::{DynTensor, Tensor};
+
+// Reader for the IDX format of MNIST and Fashion-MNIST (http://yann.lecun.com/exdb/mnist/).
+// A file starts with the magic number 0x00 0x00 <type> <number of dims>, then every dim as a
+// big-endian u32, then the data in C order. Only unsigned byte data (type 0x08) is supported,
+// which is what the image and label files use. The files must be decompressed first
+
+const UNSIGNED_BYTE: u8 = 0x08;
+
+#[derive(Debug)]
+pub enum IdxError {
+    Io(io::Error),
+    // The first two bytes of the magic number must be zero
+    BadMagic([u8; 4]),
+    UnsupportedType(u8),
+    // The product of the dims overflows usize
+    TooLarge(Vec<usize>),
+    // Number of data bytes the header announces vs the number in the file
+    Truncated { expected: usize, found: usize },
+    TrailingData { expected: usize },
+    // The dims in the file don't fit the tensor they are loaded into
+    ShapeMismatch { expected: Vec<usize>, found: Vec<usize> },
+    LabelOutOfRange { index: usize, label: u8, n_classes: usize }
+}
+
+impl fmt::Display for IdxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IdxError::Io(err) => write!(f, "idx: {}", err),
+            IdxError::BadMagic(magic) => write!(f, "idx: bad magic number {:02x?}, not an IDX file", magic),
+            IdxError::UnsupportedType(code) => write!(f, "idx: unsupported data type 0x{:02x}, only unsigned bytes (0x08) are supported", code),
+            IdxError::TooLarge(dims) => write!(f, "idx: dims {:?} are too large", dims),
+            IdxError::Truncated { expected, found } => write!(f, "idx: file truncated, expected {} data bytes but found {}", expected, found),
+            IdxError::TrailingData { expected } => write!(f, "idx: unexpected data after the {} bytes announced by the header", expected),
+            IdxError::ShapeMismatch { expected, found } => write!(f, "idx: expected dims {:?} but the file has {:?}", expected, found),
+            IdxError::LabelOutOfRange { index, label, n_classes } => write!(f, "idx: label {} of sample {} is out of range for {} classes", label, index, n_classes)
+        }
+    }
+}
+
+impl std::error::Error for IdxError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            IdxError::Io(err) => Some(err),
+            _ => None
+        }
+    }
+}
+
+impl From<io::Error> for IdxError {
+    fn from(err: io::Error) -> Self {
+        IdxError::Io(err)
+    }
+}
+
+// Range the u8 pixels are mapped to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PixelScale {
+    // pixel / 255
+    #[default]
+    ZeroToOne,
+    // (pixel - 127.5) / 127.5
+    MinusOneToOne
+}
+
+impl PixelScale {
+    fn apply(&self, pixel: u8) -> f32 {
+        match self {
+            PixelScale::ZeroToOne => pixel as f32 / 255.0,
+            PixelScale::MinusOneToOne => (pixel as f32 - 127.5) / 127.5
+        }
+    }
+}
+
+// Contents of an IDX file with its dims as stored in the header
+#[derive(Debug, Clone, PartialEq)]
+pub struct IdxData {
+    pub dims: Vec<usize>,
+    pub data: Vec<u8>
+}
+
+pub fn read_idx<R: Read>(reader: &mut R) -> Result<IdxData, IdxError> {
+    let dims = read_header(reader)?;
+    read_data(reader, dims)
+}
+
+// Magic number and dims. Callers check the dims before `read_data` reads the payload
+fn read_header<R: Read>(reader: &mut R) -> Result<Vec<usize>, IdxError> {
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    if magic[0] != 0 || magic[1] != 0 {
+        return Err(IdxError::BadMagic(magic))
+    }
+    if magic[2] != UNSIGNED_BYTE {
+        return Err(IdxError::UnsupportedType(magic[2]))
+    }
+    let mut dims = Vec::with_capacity(magic[3] as usize);
+    for _ in 0..magic[3] {
+        let mut dim = [0u8; 4];
+        reader.read_exact(&mut dim)?;
+        dims.push(u32::from_be_bytes(dim) as usize);
+    }
+    if dims.iter().try_fold(1usize, |acc, &dim| acc.checked_mul(dim)).is_none() {
+        return Err(IdxError::TooLarge(dims))
+    }
+    Ok(dims)
+}
+
+fn read_data<R: Read>(reader: &mut R, dims: Vec<usize>) -> Result<IdxData, IdxError> {
+    // Can't overflow, read_header checked it
+    let expected = dims.iter().product();
+    // Read through `take` without reserving, so a corrupt header can't make us allocate huge buffers up front
+    let mut data = Vec::new();
+    reader.by_ref().take(expected as u64).read_to_end(&mut data)?;
+    if data.len() != expected {
+        return Err(IdxError::Truncated { expected, found: data.len() })
+    }
+    if reader.read(&mut [0u8; 1])? != 0 {
+        return Err(IdxError::TrailingData { expected })
+    }
+    Ok(IdxData { dims, data })
+}
+
+// Images of an (N_SAMPLES, rows, cols) file, one flattened image per row
+pub fn read_images<R: Read, const N_SAMPLES: usize, const N_PIXELS: usize>(reader: &mut R, scale: PixelScale) -> Result<Tensor<f32, N_SAMPLES, N_PIXELS>, IdxError> {
+    let dims = read_header(reader)?;
+    if dims.len() != 3 || dims[0] != N_SAMPLES || dims[1] * dims[2] != N_PIXELS {
+        return Err(IdxError::ShapeMismatch { expected: vec![N_SAMPLES, N_PIXELS], found: dims })
+    }
+    let idx = read_data(reader, dims)?;
+    let pixels = idx.data.iter().map(|&pixel| scale.apply(pixel)).collect();
+    let images = DynTensor::from_vec(N_SAMPLES, N_PIXELS, pixels).expect("data length was checked against the dims");
+    Ok(Tensor::try_from(images).expect("dims were checked against the tensor shape"))
+}
+
+// Class indices of an (N_SAMPLES) label file
+pub fn read_labels<R: Read, const N_SAMPLES: usize, const N_CLASSES: usize>(reader: &mut R) -> Result<Targets<N_SAMPLES, N_CLASSES>, IdxError> {
+    let dims = read_header(reader)?;
+    if dims != [N_SAMPLES] {
+        return Err(IdxError::ShapeMismatch { expected: vec![N_SAMPLES], found: dims })
+    }
+    let idx = read_data(reader, dims)?;
+    let mut labels: Tensor<usize, N_SAMPLES, 1> = Tensor::new();
+    for (index, &label) in idx.data.iter().enumerate() {
+        if label as usize >= N_CLASSES {
+            return Err(IdxError::LabelOutOfRange { index, label, n_classes: N_CLASSES })
+        }
+        labels[(index, 0)] = label as usize;
+    }
+    Ok(Targets::categorical(labels))
+}
+
+// e.g. load_images::<60000, 784>("train-images-idx3-ubyte", PixelScale::ZeroToOne)
+pub fn load_images<const N_SAMPLES: usize, const N_PIXELS: usize>(path: impl AsRef<Path>, scale: PixelScale) -> Result<Tensor<f32, N_SAMPLES, N_PIXELS>, IdxError> {
+    read_images(&mut BufReader::new(File::open(path)?), scale)
+}
+
+// e.g. load_labels::<60000, 10>("train-labels-idx1-ubyte")
+pub fn load_labels<const N_SAMPLES: usize, const N_CLASSES: usize>(path: impl AsRef<Path>) -> Result<Targets<N_SAMPLES, N_CLASSES>, IdxError> {
+    read_labels(&mut BufReader::new(File::open(path)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn idx(dims: &[u32], data: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![0, 0, UNSIGNED_BYTE, dims.len() as u8];
+        for dim in dims {
+            bytes.extend(dim.to_be_bytes());
+        }
+        bytes.extend(data);
+        bytes
+    }
+
+    #[test]
+    fn reads_images_and_labels() {
+        let bytes = idx(&[2, 1, 2], &[0, 255, 255, 0]);
+        let images: Tensor<f32, 2, 2> = read_images(&mut bytes.as_slice(), PixelScale::ZeroToOne).unwrap();
+        assert_eq!(images, Tensor::from_data([[0.0, 1.0], [1.0, 0.0]]));
+        let images: Tensor<f32, 2, 2> = read_images(&mut bytes.as_slice(), PixelScale::MinusOneToOne).unwrap();
+        assert_eq!(images[(0, 0)], -1.0);
+
+        let labels: Targets<3, 10> = read_labels(&mut idx(&[3], &[7, 0, 9]).as_slice()).unwrap();
+        assert_eq!(labels.class_ids(), Tensor::from_data([[7], [0], [9]]));
+    }
+
+    #[test]
+    fn rejects_malformed_files() {
+        let err = read_idx(&mut [1u8, 0, 8, 1].as_slice()).unwrap_err();
+        assert!(matches!(err, IdxError::BadMagic(_)));
+        let err = read_idx(&mut [0u8, 0, 0x0d, 1].as_slice()).unwrap_err();
+        assert!(matches!(err, IdxError::UnsupportedType(0x0d)));
+        let err = read_idx(&mut idx(&[4], &[1, 2]).as_slice()).unwrap_err();
+        assert!(matches!(err, IdxError::Truncated { expected: 4, found: 2 }));
+        let err = read_idx(&mut idx(&[1], &[1, 2]).as_slice()).unwrap_err();
+        assert!(matches!(err, IdxError::TrailingData { expected: 1 }));
+        let err = read_images::<_, 2, 3>(&mut idx(&[2, 1, 2], &[0; 4]).as_slice(), PixelScale::ZeroToOne).unwrap_err();
+        assert!(matches!(err, IdxError::ShapeMismatch { .. }));
+        // The dims are checked before the data is read
+        let err = read_labels::<_, 2, 3>(&mut idx(&[3], &[]).as_slice()).unwrap_err();
+        assert!(matches!(err, IdxError::ShapeMismatch { .. }));
+        let err = read_labels::<_, 2, 3>(&mut idx(&[2], &[1, 3]).as_slice()).unwrap_err();
+        assert_eq!(err.to_string(), "idx: label 3 of sample 1 is out of range for 3 classes");
+    }
+
+    #[test]
+    fn rejects_oversized_headers() {
+        // Only the header, the dims alone would need more than usize::MAX bytes
+        let err = read_idx(&mut idx(&[u32::MAX; 3], &[]).as_slice()).unwrap_err();
+        assert!(matches!(err, IdxError::TooLarge(_)), "{}", err);
+        let err = read_idx(&mut idx(&[u32::MAX], &[1, 2]).as_slice()).unwrap_err();
+        assert!(matches!(err, IdxError::Truncated { found: 2, .. }));
+        let err = read_images::<_, 0, 4>(&mut idx(&[0, u32::MAX, u32::MAX], &[]).as_slice(), PixelScale::ZeroToOne).unwrap_err();
+        assert!(matches!(err, IdxError::ShapeMismatch { .. }));
+    }
+}
@@ -0,0 +1,92 @@
+pub mod idx;
+pub mod csv;
+
+use rand::SeedableRng;
+use rand::distr::Distribution;
+use rand::rngs::StdRng;
+
+use crate::initializer::Gaussian;
+use crate::metrics::Targets;
+use crate::tensor::Tensor;
+
+// Toy datasets from the nnfs book. Every generator is deterministic for a given seed,
+// `noise` is the standard deviation of the gaussian noise added to the samples.
+// Classification samples are grouped by class: the first N_SAMPLES / N_CLASSES samples
+// are class 0 and so on, any remainder goes to the first classes one sample each
+
+// Class of the i-th sample and its position within the class as a fraction in [0, 1]
+fn class_position<const N_SAMPLES: usize, const N_CLASSES: usize>(i: usize) -> (usize, f32) {
+    let class = i * N_CLASSES / N_SAMPLES;
+    let start = (class * N_SAMPLES).div_ceil(N_CLASSES);
+    let end = ((class + 1) * N_SAMPLES).div_ceil(N_CLASSES);
+    let position = if end - start > 1 { (i - start) as f32 / (end - start - 1) as f32 } else { 0.0 };
+    (class, position)
+}
+
+// N_CLASSES interleaved spiral arms around the origin, one per class
+pub fn spiral<const N_SAMPLES: usize, const N_CLASSES: usize>(noise: f32, seed: u64) -> (Tensor<f32, N_SAMPLES, 2>, Targets<N_SAMPLES, N_CLASSES>) {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let gaussian = Gaussian::new(0.0, noise);
+    let mut features: Tensor<f32, N_SAMPLES, 2> = Tensor::new();
+    let mut targets: Tensor<usize, N_SAMPLES, 1> = Tensor::new();
+    for i in 0..N_SAMPLES {
+        let (class, r) = class_position::<N_SAMPLES, N_CLASSES>(i);
+        let t = (class as f32 + r) * 4.0 + gaussian.sample(&mut rng);
+        features[(i, 0)] = r * (t * 2.5).sin();
+        features[(i, 1)] = r * (t * 2.5).cos();
+        targets[(i, 0)] = class;
+    }
+    (features, Targets::categorical(targets))
+}
+
+// One blob of points per class, the blobs sit side by side along the x axis
+pub fn vertical<const N_SAMPLES: usize, const N_CLASSES: usize>(noise: f32, seed: u64) -> (Tensor<f32, N_SAMPLES, 2>, Targets<N_SAMPLES, N_CLASSES>) {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let gaussian = Gaussian::new(0.0, noise);
+    let mut features: Tensor<f32, N_SAMPLES, 2> = Tensor::new();
+    let mut targets: Tensor<usize, N_SAMPLES, 1> = Tensor::new();
+    for i in 0..N_SAMPLES {
+        let (class, _) = class_position::<N_SAMPLES, N_CLASSES>(i);
+        features[(i, 0)] = class as f32 / N_CLASSES as f32 + gaussian.sample(&mut rng);
+        features[(i, 1)] = 0.5 + gaussian.sample(&mut rng);
+        targets[(i, 0)] = class;
+    }
+    (features, Targets::categorical(targets))
+}
+
+// Regression data: x evenly spaced in [0, 1) and y = sin(2 * pi * x) plus noise
+pub fn sine<const N_SAMPLES: usize>(noise: f32, seed: u64) -> (Tensor<f32, N_SAMPLES, 1>, Tensor<f32, N_SAMPLES, 1>) {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let gaussian = Gaussian::new(0.0, noise);
+    let mut x: Tensor<f32, N_SAMPLES, 1> = Tensor::new();
+    let mut y: Tensor<f32, N_SAMPLES, 1> = Tensor::new();
+    for i in 0..N_SAMPLES {
+        x[(i, 0)] = i as f32 / N_SAMPLES as f32;
+        y[(i, 0)] = (2.0 * std::f32::consts::PI * x[(i, 0)]).sin() + gaussian.sample(&mut rng);
+    }
+    (x, y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classes_are_balanced_and_seeded() {
+        let (x, y) = spiral::<10, 3>(0.2, 1);
+        let ids = y.class_ids();
+        let counts = (0..3).map(|c| (0..10).filter(|&i| ids[(i, 0)] == c).count()).collect::<Vec<_>>();
+        assert_eq!(counts, vec![4, 3, 3]);
+        assert_eq!(spiral::<10, 3>(0.2, 1).0, x);
+        assert_ne!(spiral::<10, 3>(0.2, 2).0, x);
+    }
+
+    #[test]
+    fn noiseless_data() {
+        let (x, _) = vertical::<4, 2>(0.0, 0);
+        assert_eq!(x, Tensor::from_data([[0.0, 0.5], [0.0, 0.5], [0.5, 0.5], [0.5, 0.5]]));
+        let (x, y) = sine::<4>(0.0, 0);
+        assert_eq!(x, Tensor::from_data([[0.0], [0.25], [0.5], [0.75]]));
+        assert!((y[(1, 0)] - 1.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,173 @@
+use std::fmt;
+
+use rand::distr::{Distribution, Uniform};
+
+use crate::tensor::Tensor;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InitializerError {
+    // low must be below high and both finite
+    InvalidRange { low: f32, high: f32 }
+}
+
+impl fmt::Display for InitializerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InitializerError::InvalidRange { low, high } => write!(f, "initializer: invalid uniform range [{}, {})", low, high)
+        }
+    }
+}
+
+impl std::error::Error for InitializerError {}
+
+// Normal distribution sampled with the Box-Muller transform
+#[derive(Clone, Copy, Debug)]
+pub struct Gaussian {
+    mean: f32,
+    std: f32
+}
+
+impl Gaussian {
+    pub fn new(mean: f32, std: f32) -> Self {
+        Gaussian { mean, std }
+    }
+}
+
+impl Distribution<f32> for Gaussian {
+    fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> f32 {
+        // u1 is drawn from (0, 1] so that ln(u1) stays finite
+        let u1: f32 = 1.0 - rng.random::<f32>();
+        let u2: f32 = rng.random::<f32>();
+        let z = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f32::consts::PI * u2).cos();
+        self.mean + self.std * z
+    }
+}
+
+// How the initial values of a parameter tensor are drawn. The Glorot, He and LeCun schemes
+// scale their distribution from the number of inputs (fan_in) and outputs (fan_out), which are
+// the rows and columns of the tensor being drawn, as for the weights of a dense layer
+#[derive(Clone, Copy, Debug)]
+pub enum Initializer {
+    Zeros,
+    Constant(f32),
+    // Built with `Initializer::uniform`, which checks the range
+    Uniform(Uniform<f32>),
+    Normal { mean: f32, std: f32 },
+    GlorotUniform,
+    GlorotNormal,
+    HeUniform,
+    HeNormal,
+    LecunUniform,
+    LecunNormal
+}
+
+impl Initializer {
+    // Uniform in [low, high)
+    pub fn uniform(low: f32, high: f32) -> Result<Self, InitializerError> {
+        Uniform::new(low, high).map(Initializer::Uniform).map_err(|_| InitializerError::InvalidRange { low, high })
+    }
+
+    pub fn init<R, const ROWS: usize, const COLS: usize>(&self, rng: &mut R) -> Tensor<f32, ROWS, COLS>
+    where
+        R: rand::Rng + ?Sized
+    {
+        let fan_in = ROWS as f32;
+        let fan_out = COLS as f32;
+        match *self {
+            Initializer::Zeros => Tensor::new(),
+            Initializer::Constant(val) => Tensor::fill(val),
+            Initializer::Uniform(distr) => Tensor::sample_with(rng, distr),
+            Initializer::Normal { mean, std } => Tensor::sample_with(rng, Gaussian::new(mean, std)),
+            Initializer::GlorotUniform => {
+                let limit = (6.0 / (fan_in + fan_out)).sqrt();
+                uniform(-limit, limit, rng)
+            }
+            Initializer::GlorotNormal => Tensor::sample_with(rng, Gaussian::new(0.0, (2.0 / (fan_in + fan_out)).sqrt())),
+            Initializer::HeUniform => {
+                let limit = (6.0 / fan_in).sqrt();
+                uniform(-limit, limit, rng)
+            }
+            Initializer::HeNormal => Tensor::sample_with(rng, Gaussian::new(0.0, (2.0 / fan_in).sqrt())),
+            Initializer::LecunUniform => {
+                let limit = (3.0 / fan_in).sqrt();
+                uniform(-limit, limit, rng)
+            }
+            Initializer::LecunNormal => Tensor::sample_with(rng, Gaussian::new(0.0, (1.0 / fan_in).sqrt()))
+        }
+    }
+}
+
+fn uniform<R, const ROWS: usize, const COLS: usize>(low: f32, high: f32, rng: &mut R) -> Tensor<f32, ROWS, COLS>
+where
+    R: rand::Rng + ?Sized
+{
+    // The limits are only infinite for a fan of 0, in which case there is nothing to draw
+    if ROWS == 0 || COLS == 0 {
+        return Tensor::new()
+    }
+    Tensor::sample_with(rng, Uniform::new(low, high).expect("limits of a non-empty tensor are finite"))
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    use super::*;
+    use crate::tensor::Axis;
+
+    // Largest absolute value and population variance of the elements
+    fn stats<const ROWS: usize, const COLS: usize>(tensor: &Tensor<f32, ROWS, COLS>) -> (f32, f32) {
+        let n = (ROWS * COLS) as f32;
+        let mean = tensor.sum() / n;
+        let mut max_abs: f32 = 0.0;
+        let mut var = 0.0;
+        for i in 0..ROWS {
+            for j in 0..COLS {
+                max_abs = max_abs.max(tensor[(i, j)].abs());
+                var += (tensor[(i, j)] - mean).powi(2) / n;
+            }
+        }
+        (max_abs, var)
+    }
+
+    fn assert_near(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 0.05 * expected, "{} is not within 5% of {}", actual, expected);
+    }
+
+    #[test]
+    fn fans_come_from_the_shape() {
+        let mut rng = StdRng::seed_from_u64(7);
+        // Glorot: limit sqrt(6 / (fan_in + fan_out)), variance 2 / (fan_in + fan_out)
+        let glorot: Tensor<f32, 100, 200> = Initializer::GlorotUniform.init(&mut rng);
+        let (max_abs, var) = stats(&glorot);
+        assert!(max_abs <= (6.0f32 / 300.0).sqrt() && max_abs > 0.95 * (6.0f32 / 300.0).sqrt());
+        assert_near(var, 2.0 / 300.0);
+        let glorot: Tensor<f32, 100, 200> = Initializer::GlorotNormal.init(&mut rng);
+        assert_near(stats(&glorot).1, 2.0 / 300.0);
+
+        // He: limit sqrt(6 / fan_in), variance 2 / fan_in
+        let he: Tensor<f32, 200, 100> = Initializer::HeUniform.init(&mut rng);
+        let (max_abs, var) = stats(&he);
+        assert!(max_abs <= (6.0f32 / 200.0).sqrt() && max_abs > 0.95 * (6.0f32 / 200.0).sqrt());
+        assert_near(var, 2.0 / 200.0);
+        let he: Tensor<f32, 200, 100> = Initializer::HeNormal.init(&mut rng);
+        assert_near(stats(&he).1, 2.0 / 200.0);
+
+        // A bias row has a fan_in of 1
+        let bias: Tensor<f32, 1, 5000> = Initializer::HeNormal.init(&mut rng);
+        assert_near(stats(&bias).1, 2.0);
+        let empty: Tensor<f32, 0, 3> = Initializer::HeUniform.init(&mut rng);
+        assert_eq!(empty, Tensor::new());
+    }
+
+    #[test]
+    fn uniform_checks_its_range() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let values: Tensor<f32, 50, 50> = Initializer::uniform(2.0, 3.0).unwrap().init(&mut rng);
+        assert_eq!(values.all(Axis::Row, |el| (2.0..3.0).contains(el)).unwrap_row(), Tensor::fill(true));
+        assert_eq!(Initializer::uniform(1.0, 1.0).unwrap_err(), InitializerError::InvalidRange { low: 1.0, high: 1.0 });
+        assert!(Initializer::uniform(0.0, f32::NAN).is_err());
+        assert_eq!(Initializer::uniform(2.0, -2.0).unwrap_err().to_string(), "initializer: invalid uniform range [2, -2)");
+    }
+}
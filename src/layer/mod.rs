@@ -1,25 +1,200 @@
-use crate::tensor::Tensor;
+use crate::initializer::Initializer;
+use crate::tensor::{Axis, DynTensor, ShapeError, Tensor};
 
 
-pub trait Layer<const N_INPUTS: usize, const N_NEURONS: usize, const BATCH_SIZE: usize> {
-    fn forward(self, inputs: Tensor<f32, BATCH_SIZE, N_INPUTS>) -> Tensor<f32, BATCH_SIZE, N_NEURONS>;
-    fn new() -> DenseLayer<N_INPUTS, N_NEURONS, BATCH_SIZE>;
+// The batch size is not part of the layer type, the same layer can be trained on
+// batches of 300 and then run on a single sample.
+// Layers are borrowed mutably so they can cache what backward needs and be run for any number of iterations
+pub trait Layer<const N_INPUTS: usize, const N_NEURONS: usize> {
+    fn new() -> Self;
+    fn forward<const BATCH_SIZE: usize>(&mut self, inputs: &Tensor<f32, BATCH_SIZE, N_INPUTS>) -> Tensor<f32, BATCH_SIZE, N_NEURONS>;
+    // Takes the gradient of the loss w.r.t. this layer's outputs and returns the gradient w.r.t. its inputs.
+    // Fails if the last forward pass didn't run with the same batch size
+    fn backward<const BATCH_SIZE: usize>(&mut self, dvalues: &Tensor<f32, BATCH_SIZE, N_NEURONS>) -> Result<Tensor<f32, BATCH_SIZE, N_INPUTS>, ShapeError>;
 }
 
-pub struct DenseLayer<const N_INPUTS: usize, const N_NEURONS: usize, const BATCH_SIZE: usize> {
-    weights: Tensor<f32, N_INPUTS, N_NEURONS>,
-    biases: Tensor<f32, 1, N_NEURONS>
+pub struct DenseLayer<const N_INPUTS: usize, const N_NEURONS: usize> {
+    pub(crate) weights: Tensor<f32, N_INPUTS, N_NEURONS>,
+    pub(crate) biases: Tensor<f32, 1, N_NEURONS>,
+    // Inputs of the last forward pass, needed to compute dweights. Dynamic since the batch size can change between passes
+    inputs: DynTensor<f32>,
+    pub(crate) dweights: Tensor<f32, N_INPUTS, N_NEURONS>,
+    pub(crate) dbiases: Tensor<f32, 1, N_NEURONS>,
+    // Optimizer state, kept next to the parameters it belongs to
+    pub(crate) weight_momentums: Tensor<f32, N_INPUTS, N_NEURONS>,
+    pub(crate) bias_momentums: Tensor<f32, 1, N_NEURONS>,
+    pub(crate) weight_cache: Tensor<f32, N_INPUTS, N_NEURONS>,
+    pub(crate) bias_cache: Tensor<f32, 1, N_NEURONS>,
+    // Id of the optimizer the state belongs to
+    optimizer_id: Option<usize>
+}
+
+impl<const N_INPUTS: usize, const N_NEURONS: usize> DenseLayer<N_INPUTS, N_NEURONS> {
+    // Same as `Layer::new` but draws the initial weights from `rng`, use a seeded rng for reproducible runs
+    pub fn new_with_rng<R: rand::Rng + ?Sized>(rng: &mut R) -> Self {
+        let weight_init = Initializer::uniform(0.0, 0.01).expect("0.0 < 0.01");
+        DenseLayer::with_initializers(weight_init, Initializer::Zeros, rng)
+    }
+
+    // Fans are taken from each tensor's shape: N_INPUTS x N_NEURONS for the weights, 1 x N_NEURONS for the biases
+    pub fn with_initializers<R: rand::Rng + ?Sized>(weight_init: Initializer, bias_init: Initializer, rng: &mut R) -> Self {
+        DenseLayer::from_params(weight_init.init(rng), bias_init.init(rng))
+    }
+
+    // Layer with the given parameters, e.g. trained weights loaded from disk
+    pub fn from_params(weights: Tensor<f32, N_INPUTS, N_NEURONS>, biases: Tensor<f32, 1, N_NEURONS>) -> Self {
+        DenseLayer {
+            weights,
+            biases,
+            inputs: DynTensor::new(0, N_INPUTS),
+            dweights: Tensor::new(),
+            dbiases: Tensor::new(),
+            weight_momentums: Tensor::new(),
+            bias_momentums: Tensor::new(),
+            weight_cache: Tensor::new(),
+            bias_cache: Tensor::new(),
+            optimizer_id: None
+        }
+    }
+
+    // Called by an optimizer before it updates the layer, the state of any other optimizer is reset to zeros
+    pub(crate) fn claim_optimizer_state(&mut self, optimizer_id: usize) {
+        if self.optimizer_id != Some(optimizer_id) {
+            self.weight_momentums = Tensor::new();
+            self.bias_momentums = Tensor::new();
+            self.weight_cache = Tensor::new();
+            self.bias_cache = Tensor::new();
+            self.optimizer_id = Some(optimizer_id);
+        }
+    }
+
+    pub fn weights(&self) -> &Tensor<f32, N_INPUTS, N_NEURONS> {
+        &self.weights
+    }
+
+    pub fn biases(&self) -> &Tensor<f32, 1, N_NEURONS> {
+        &self.biases
+    }
+
+    pub fn dweights(&self) -> &Tensor<f32, N_INPUTS, N_NEURONS> {
+        &self.dweights
+    }
+
+    pub fn dbiases(&self) -> &Tensor<f32, 1, N_NEURONS> {
+        &self.dbiases
+    }
 }
 
 // For now we use concrete f32 for dense layer. No need for generic
-impl <const N_INPUTS: usize, const N_NEURONS: usize, const BATCH_SIZE: usize> Layer<N_INPUTS, N_NEURONS, BATCH_SIZE> for DenseLayer<N_INPUTS, N_NEURONS, BATCH_SIZE> {
-    fn new() -> DenseLayer<N_INPUTS, N_NEURONS, BATCH_SIZE> {
-        let weights: Tensor<f32, N_INPUTS, N_NEURONS> = Tensor::rand_fill() * 0.01;
-        let biases: Tensor<f32, 1, N_NEURONS> = Tensor::new();
-        DenseLayer { weights: weights, biases: biases }
+impl <const N_INPUTS: usize, const N_NEURONS: usize> Layer<N_INPUTS, N_NEURONS> for DenseLayer<N_INPUTS, N_NEURONS> {
+    fn new() -> Self {
+        DenseLayer::new_with_rng(&mut rand::rng())
+    }
+
+    fn forward<const BATCH_SIZE: usize>(&mut self, inputs: &Tensor<f32, BATCH_SIZE, N_INPUTS>) -> Tensor<f32, BATCH_SIZE, N_NEURONS> {
+        self.inputs = DynTensor::from(inputs);
+        inputs * &self.weights + self.biases.broadcast::<BATCH_SIZE, N_NEURONS>()
     }
 
-    fn forward(self, inputs: Tensor<f32, BATCH_SIZE, N_INPUTS>) -> Tensor<f32, BATCH_SIZE, N_NEURONS> {
-        inputs * self.weights + self.biases.broadcast::<BATCH_SIZE, N_NEURONS>()
+    fn backward<const BATCH_SIZE: usize>(&mut self, dvalues: &Tensor<f32, BATCH_SIZE, N_NEURONS>) -> Result<Tensor<f32, BATCH_SIZE, N_INPUTS>, ShapeError> {
+        let inputs: Tensor<f32, BATCH_SIZE, N_INPUTS> = Tensor::try_from(&self.inputs)?;
+        self.dweights = &inputs.transpose() * dvalues;
+        self.dbiases = dvalues.sum_axis(Axis::Col).unwrap_col();
+        Ok(dvalues * &self.weights.transpose())
     }
-}
\ No newline at end of file
+}
+
+// Only the parameters are serialized, gradients, optimizer state and cached inputs start empty again
+#[cfg(feature = "serde")]
+impl<const N_INPUTS: usize, const N_NEURONS: usize> serde::Serialize for DenseLayer<N_INPUTS, N_NEURONS> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("DenseLayer", 2)?;
+        state.serialize_field("weights", &self.weights)?;
+        state.serialize_field("biases", &self.biases)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, const N_INPUTS: usize, const N_NEURONS: usize> serde::Deserialize<'de> for DenseLayer<N_INPUTS, N_NEURONS> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "DenseLayer")]
+        struct Params<const N_INPUTS: usize, const N_NEURONS: usize> {
+            weights: Tensor<f32, N_INPUTS, N_NEURONS>,
+            biases: Tensor<f32, 1, N_NEURONS>
+        }
+        let params = Params::<N_INPUTS, N_NEURONS>::deserialize(deserializer)?;
+        Ok(DenseLayer::from_params(params.weights, params.biases))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimizer::Optimizer;
+    use crate::optimizer::sgd::SGD;
+
+    #[test]
+    fn backward() {
+        let mut layer = DenseLayer::<2, 3>::from_params(Tensor::from_data([[1.0, -2.0, 0.5], [0.0, 1.0, -1.0]]), Tensor::from_data([[0.1, 0.2, 0.3]]));
+        let inputs: Tensor<f32, 2, 2> = Tensor::from_data([[1.0, 2.0], [-1.0, 0.5]]);
+        let dvalues: Tensor<f32, 2, 3> = Tensor::from_data([[1.0, 0.0, -1.0], [0.5, 2.0, 0.0]]);
+        layer.forward(&inputs);
+        let dinputs = layer.backward(&dvalues).unwrap();
+        // dweights = inputs^T * dvalues, dbiases = column sums of dvalues, dinputs = dvalues * weights^T
+        assert_eq!(layer.dweights(), &Tensor::from_data([[0.5, -2.0, -1.0], [2.25, 1.0, -2.0]]));
+        assert_eq!(layer.dbiases(), &Tensor::from_data([[1.5, 2.0, -1.0]]));
+        assert_eq!(dinputs, Tensor::from_data([[0.5, 1.0], [-3.5, 2.0]]));
+    }
+
+    #[test]
+    fn seeded_layers_are_reproducible() {
+        use rand::SeedableRng;
+        use rand::rngs::StdRng;
+
+        let a = DenseLayer::<3, 4>::new_with_rng(&mut StdRng::seed_from_u64(7));
+        let b = DenseLayer::<3, 4>::new_with_rng(&mut StdRng::seed_from_u64(7));
+        let c = DenseLayer::<3, 4>::new_with_rng(&mut StdRng::seed_from_u64(8));
+        assert_eq!(a.weights(), b.weights());
+        assert_eq!(a.biases(), b.biases());
+        assert_ne!(a.weights(), c.weights());
+    }
+
+    #[test]
+    fn batch_size_is_picked_per_call() {
+        let mut layer = DenseLayer::<2, 3>::from_params(Tensor::from_data([[1.0, -2.0, 0.5], [0.0, 1.0, -1.0]]), Tensor::from_data([[0.1, 0.2, 0.3]]));
+        let batch: Tensor<f32, 4, 2> = Tensor::from_data([[1.0, 2.0], [-1.0, 0.5], [0.0, 0.0], [2.0, -3.0]]);
+        let mut optimizer = SGD::new(0.1, 0.0, 0.0);
+        for _ in 0..3 {
+            layer.forward(&batch);
+            layer.backward(&Tensor::<f32, 4, 3>::fill(1.0)).unwrap();
+            optimizer.pre_update_params();
+            optimizer.update_params(&mut layer);
+            optimizer.post_update_params();
+        }
+
+        let outputs = layer.forward(&batch);
+        let single = layer.forward(&batch.select_rows::<1>(&[3]));
+        for j in 0..3 {
+            assert_eq!(single[(0, j)], outputs[(3, j)]);
+        }
+        // The last forward pass ran on a single sample
+        let err = layer.backward(&Tensor::<f32, 4, 3>::fill(1.0)).unwrap_err();
+        assert_eq!(err.to_string(), "convert: incompatible shapes 1x2 and 4x2");
+        assert!(DenseLayer::<2, 3>::new().backward(&Tensor::<f32, 1, 3>::new()).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let layer = DenseLayer::<2, 3>::from_params(Tensor::from_data([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]), Tensor::from_data([[0.5, 0.0, -0.5]]));
+        let json = serde_json::to_string(&layer).unwrap();
+        assert_eq!(json, r#"{"weights":[[1.0,2.0,3.0],[4.0,5.0,6.0]],"biases":[[0.5,0.0,-0.5]]}"#);
+        let restored: DenseLayer<2, 3> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.weights(), layer.weights());
+        assert_eq!(restored.biases(), layer.biases());
+        assert!(serde_json::from_str::<DenseLayer<3, 3>>(&json).is_err());
+    }
+}
@@ -1,18 +1,12 @@
-pub mod layer;
-pub mod tensor;
-pub mod activator;
-pub mod metrics;
-mod test_data;
-
-use crate::activator::relu::ReLU;
-use crate::activator::softmax::Softmax;
-use crate::layer::{DenseLayer, Layer};
-use crate::metrics::accuracy::Accuracy;
-use crate::tensor::Tensor;
-use crate::activator::{Activator};
-use crate::metrics::Targets;
-// use crate::tensor::AxisRes;
-use test_data::{test_data, test_targets};
+use rustai::activator::relu::ReLU;
+use rustai::activator::softmax_cross_entropy::SoftmaxCrossEntropy;
+use rustai::dataloader::{DataLoader, LastBatch};
+use rustai::layer::{DenseLayer, Layer};
+use rustai::model::Sequential;
+use rustai::optimizer::adam::Adam;
+// use rustai::tensor::AxisRes;
+use rustai::datasets;
+use rustai::trainer::{ProgressPrinter, Trainer};
 
 fn main() {
     // let weights: tensor::Tensor<f32, 3, 4> = Tensor::from_data([[0.0, 1.0, 0.0, 3.0],[0.0, -0.91, 0.26, -0.5],[0.0, -0.27, 0.17, 0.87]]);
@@ -21,16 +15,17 @@ fn main() {
     // println!("{:?}", weights.all(tensor::Axis::Col, |&x| {x == 0.0}));
     // println!("{:?}", weights.transpose());
 
-    let layer_1 = DenseLayer::<2, 3, 300>::new();
-    let res = layer_1.forward(test_data());
-    let activator: ReLU<300, 3> = ReLU{};
-    let activator_res = activator.forward(&res);
-    let layer_2 = DenseLayer::<3, 3, 300>::new();
-    let res_2 = layer_2.forward(activator_res);
-    let activator_2: Softmax<300, 3> = Softmax{};
-    let activator_res_2 = activator_2.forward(&res_2);
-    // let loss: CrossEntropyLoss<300, 3> = CrossEntropyLoss {};
-    // let loss_res = loss.forward(activator_res_2, Targets::onehot(test_targets()));
-    let acc: Accuracy<300, 3> = Accuracy{};
-    println!("{:?}", acc.calculate(activator_res_2, Targets::onehot(test_targets())));
-}
\ No newline at end of file
+    let model = Sequential::new(DenseLayer::<2, 64>::new(), ReLU::new())
+        .then(DenseLayer::<64, 3>::new());
+    let optimizer = Adam::new(0.02, 5e-7, 1e-7, 0.9, 0.999);
+    let mut trainer = Trainer::new(model, SoftmaxCrossEntropy::new(), optimizer);
+    trainer.add_callback(ProgressPrinter::new(100));
+    let (features, targets) = datasets::spiral::<300, 3>(0.2, 0);
+    let mut loader = DataLoader::new(&features, &targets)
+        .shuffled(42)
+        .last_batch(LastBatch::Wrap);
+    let history = trainer.fit_loader::<300, 64, 2, 3>(&mut loader, 1001);
+    if let Some(last) = history.last() {
+        println!("final loss: {:.3}, acc: {:.3}", last.loss, last.accuracy);
+    }
+}
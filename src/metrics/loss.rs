@@ -1,35 +1,115 @@
-use crate::tensor::{Tensor, TensorIndex};
+use crate::tensor::Tensor;
 
 
-pub struct CrossEntropyLoss<const BATCH_SIZE: usize, const N_INPUTS: usize> {}
+#[derive(Default)]
+pub struct CrossEntropyLoss<const N_INPUTS: usize> {}
 
-pub trait Loss<const BATCH_SIZE: usize, const N_INPUTS: usize>{
-    fn forward(&self, inputs: Tensor<f32, BATCH_SIZE, N_INPUTS>, targets: super::Targets<BATCH_SIZE, N_INPUTS>) -> f32;
+// Both passes panic if the targets fail `Targets::validate`, e.g. a class index >= N_INPUTS
+pub trait Loss<const N_INPUTS: usize>{
+    fn forward<const BATCH_SIZE: usize>(&self, inputs: &Tensor<f32, BATCH_SIZE, N_INPUTS>, targets: &super::Targets<BATCH_SIZE, N_INPUTS>) -> f32;
+    // Gradient of the mean loss w.r.t. the predictions, the start of the backprop chain
+    fn backward<const BATCH_SIZE: usize>(&self, inputs: &Tensor<f32, BATCH_SIZE, N_INPUTS>, targets: &super::Targets<BATCH_SIZE, N_INPUTS>) -> Tensor<f32, BATCH_SIZE, N_INPUTS>;
 }
 
-impl <const BATCH_SIZE: usize, const N_INPUTS: usize> Loss<BATCH_SIZE, N_INPUTS> for CrossEntropyLoss<BATCH_SIZE, N_INPUTS> {
-    fn forward(&self, inputs: Tensor<f32, BATCH_SIZE, N_INPUTS>, targets: super::Targets<BATCH_SIZE, N_INPUTS>) -> f32 {
-        // TODO not clone the inputs for efficiency
-        let mut clipped_inputs = inputs.clone();
-        clipped_inputs.apply(|el| {
-            if *el == 1 as f32 {
-                1 as f32 - f32::MIN
-            } else if *el == 0 as f32 {
-                0 as f32 + f32::MIN
-            } else {
-                *el
-            }
-        });
+// Predictions are clipped away from 0 and 1 so that neither ln nor the division can blow up
+const CLIP_EPSILON: f32 = 1e-7;
+
+fn check<const BATCH_SIZE: usize, const N_INPUTS: usize>(targets: &super::Targets<BATCH_SIZE, N_INPUTS>) {
+    if let Err(err) = targets.validate() {
+        panic!("{}", err)
+    }
+}
+
+fn clip(el: f32) -> f32 {
+    el.clamp(CLIP_EPSILON, 1.0 - CLIP_EPSILON)
+}
+
+impl <const N_INPUTS: usize> Loss<N_INPUTS> for CrossEntropyLoss<N_INPUTS> {
+    fn forward<const BATCH_SIZE: usize>(&self, inputs: &Tensor<f32, BATCH_SIZE, N_INPUTS>, targets: &super::Targets<BATCH_SIZE, N_INPUTS>) -> f32 {
+        check(targets);
+        // Predicted probability of the target class of every sample
+        let mut masked_result: Tensor<f32, BATCH_SIZE, 1> = Tensor::new();
+        for i in 0..BATCH_SIZE {
+            let class = match targets {
+                super::Targets::categorical(t) => t[(i, 0)],
+                super::Targets::onehot(t) => (0..N_INPUTS).position(|j| t[(i, j)] == 1).expect("targets were validated")
+            };
+            masked_result[(i, 0)] = clip(inputs[(i, class)]);
+        }
+        masked_result.apply(|el| -el.ln());
+        masked_result.mean()
+    }
+
+    fn backward<const BATCH_SIZE: usize>(&self, inputs: &Tensor<f32, BATCH_SIZE, N_INPUTS>, targets: &super::Targets<BATCH_SIZE, N_INPUTS>) -> Tensor<f32, BATCH_SIZE, N_INPUTS> {
+        check(targets);
+        // d(-ln(p_target)) / dp = -1 / p_target for the target class and 0 elsewhere
+        let mut dinputs: Tensor<f32, BATCH_SIZE, N_INPUTS> = Tensor::new();
         match targets {
-            // Targets::categorical(t) => {
-                
-            // },
+            super::Targets::categorical(t) => {
+                for i in 0..BATCH_SIZE {
+                    let class = t[(i, 0)];
+                    dinputs[(i, class)] = -1.0 / clip(inputs[(i, class)]);
+                }
+            },
             super::Targets::onehot(t) => {
-                // TODO Process result
-                let mut masked_result = clipped_inputs.index_cols(TensorIndex::Mask(t)).expect("Error while indexing with onehot targets");
-                masked_result.apply(|el| -el.ln());
-                masked_result.mean()
+                for i in 0..BATCH_SIZE {
+                    for j in 0..N_INPUTS {
+                        dinputs[(i, j)] = -(t[(i, j)] as f32) / clip(inputs[(i, j)]);
+                    }
+                }
             }
         }
+        // Normalize so the gradient magnitude doesn't depend on the batch size
+        dinputs / BATCH_SIZE as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::{Targets, TargetsError};
+    use crate::tensor::assert_close;
+
+    fn predictions() -> Tensor<f32, 2, 3> {
+        Tensor::from_data([[0.7, 0.2, 0.1], [0.1, 0.5, 0.4]])
+    }
+
+    #[test]
+    fn cross_entropy() {
+        let loss = CrossEntropyLoss::<3> {};
+        let categorical = Targets::categorical(Tensor::from_data([[0], [1]]));
+        let onehot = Targets::onehot(Tensor::from_data([[1, 0, 0], [0, 1, 0]]));
+        let expected = -(0.7f32.ln() + 0.5f32.ln()) / 2.0;
+        for targets in [categorical, onehot] {
+            assert!((loss.forward(&predictions(), &targets) - expected).abs() < 1e-6);
+            // -1 / p of the target class, averaged over the batch of 2
+            assert_close(&loss.backward(&predictions(), &targets), [[-1.0 / 1.4, 0.0, 0.0], [0.0, -1.0, 0.0]]);
+        }
+    }
+
+    #[test]
+    fn cross_entropy_clips_predictions() {
+        let loss = CrossEntropyLoss::<2> {};
+        let targets = Targets::categorical(Tensor::from_data([[1]]));
+        let predictions: Tensor<f32, 1, 2> = Tensor::from_data([[1.0, 0.0]]);
+        assert!((loss.forward(&predictions, &targets) + CLIP_EPSILON.ln()).abs() < 1e-4);
+        assert!(loss.backward(&predictions, &targets)[(0, 1)].is_finite());
+    }
+
+    #[test]
+    fn validates_targets() {
+        let out_of_range: Targets<2, 3> = Targets::categorical(Tensor::from_data([[0], [3]]));
+        assert_eq!(out_of_range.validate(), Err(TargetsError::ClassOutOfRange { sample: 1, class: 3, n_classes: 3 }));
+        let two_ones: Targets<2, 3> = Targets::onehot(Tensor::from_data([[1, 0, 0], [0, 1, 1]]));
+        assert_eq!(two_ones.validate(), Err(TargetsError::NotOnehot { sample: 1 }));
+        let not_binary: Targets<1, 3> = Targets::onehot(Tensor::from_data([[1, 2, 0]]));
+        assert!(not_binary.validate().is_err());
+        assert!(Targets::<2, 3>::onehot(Tensor::from_data([[0, 0, 1], [1, 0, 0]])).validate().is_ok());
     }
-}
\ No newline at end of file
+
+    #[test]
+    #[should_panic(expected = "targets: class 3 of sample 1 is out of range for 3 classes")]
+    fn panics_on_invalid_targets() {
+        CrossEntropyLoss::<3> {}.forward(&predictions(), &Targets::categorical(Tensor::from_data([[0], [3]])));
+    }
+}
@@ -1,9 +1,136 @@
+use std::fmt;
+
 use crate::tensor::Tensor;
 
 pub mod loss;
 pub mod accuracy;
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TargetsError {
+    ClassOutOfRange { sample: usize, class: usize, n_classes: usize },
+    // The row of a onehot target is not all zeros but a single 1
+    NotOnehot { sample: usize }
+}
+
+impl fmt::Display for TargetsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TargetsError::ClassOutOfRange { sample, class, n_classes } => write!(f, "targets: class {} of sample {} is out of range for {} classes", class, sample, n_classes),
+            TargetsError::NotOnehot { sample } => write!(f, "targets: row {} is not onehot", sample)
+        }
+    }
+}
+
+impl std::error::Error for TargetsError {}
+
+// With the serde feature, targets are deserialized through TargetsRepr and validated, so invalid
+// class indices or onehot rows are rejected like a tensor of the wrong shape
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "TargetsRepr<BATCH_SIZE, N_INPUTS>"))]
+#[allow(non_camel_case_types)]
 pub enum Targets<const BATCH_SIZE: usize, const N_INPUTS: usize>{
-    onehot(Tensor<usize, BATCH_SIZE, N_INPUTS>)
-    // categorical(Tensor<usize, 1, BATCH_SIZE>)
-}
\ No newline at end of file
+    onehot(Tensor<usize, BATCH_SIZE, N_INPUTS>),
+    // Class index of every sample
+    categorical(Tensor<usize, BATCH_SIZE, 1>)
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+#[allow(non_camel_case_types)]
+enum TargetsRepr<const BATCH_SIZE: usize, const N_INPUTS: usize> {
+    onehot(Tensor<usize, BATCH_SIZE, N_INPUTS>),
+    categorical(Tensor<usize, BATCH_SIZE, 1>)
+}
+
+#[cfg(feature = "serde")]
+impl<const BATCH_SIZE: usize, const N_INPUTS: usize> TryFrom<TargetsRepr<BATCH_SIZE, N_INPUTS>> for Targets<BATCH_SIZE, N_INPUTS> {
+    type Error = TargetsError;
+
+    fn try_from(repr: TargetsRepr<BATCH_SIZE, N_INPUTS>) -> Result<Self, TargetsError> {
+        let targets = match repr {
+            TargetsRepr::onehot(t) => Targets::onehot(t),
+            TargetsRepr::categorical(t) => Targets::categorical(t)
+        };
+        targets.validate()?;
+        Ok(targets)
+    }
+}
+
+impl<const BATCH_SIZE: usize, const N_INPUTS: usize> Targets<BATCH_SIZE, N_INPUTS> {
+    // Every class index must be below N_INPUTS and every onehot row must hold a single 1.
+    // The methods below and the losses panic on targets that fail this check
+    pub fn validate(&self) -> Result<(), TargetsError> {
+        for sample in 0..BATCH_SIZE {
+            match self {
+                Targets::categorical(t) => {
+                    let class = t[(sample, 0)];
+                    if class >= N_INPUTS {
+                        return Err(TargetsError::ClassOutOfRange { sample, class, n_classes: N_INPUTS })
+                    }
+                }
+                Targets::onehot(t) => {
+                    let ones = (0..N_INPUTS).filter(|&j| t[(sample, j)] == 1).count();
+                    let zeros = (0..N_INPUTS).filter(|&j| t[(sample, j)] == 0).count();
+                    if ones != 1 || zeros != N_INPUTS - 1 {
+                        return Err(TargetsError::NotOnehot { sample })
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Class index of every sample, whatever the encoding
+    pub fn class_ids(&self) -> Tensor<usize, BATCH_SIZE, 1> {
+        match self {
+            Targets::categorical(t) => t.clone(),
+            Targets::onehot(t) => {
+                let mut res: Tensor<usize, BATCH_SIZE, 1> = Tensor::new();
+                for i in 0..BATCH_SIZE {
+                    res[(i, 0)] = (0..N_INPUTS).position(|j| t[(i, j)] == 1).expect("Invalid onehot targets");
+                }
+                res
+            }
+        }
+    }
+
+    // Targets of the samples at `indices` in that order
+    pub fn select_rows<const N: usize>(&self, indices: &[usize]) -> Targets<N, N_INPUTS> {
+        match self {
+            Targets::onehot(t) => Targets::onehot(t.select_rows(indices)),
+            Targets::categorical(t) => Targets::categorical(t.select_rows(indices))
+        }
+    }
+
+    pub fn to_onehot(&self) -> Tensor<usize, BATCH_SIZE, N_INPUTS> {
+        match self {
+            Targets::onehot(t) => t.clone(),
+            Targets::categorical(t) => {
+                let mut res: Tensor<usize, BATCH_SIZE, N_INPUTS> = Tensor::new();
+                for i in 0..BATCH_SIZE {
+                    res[(i, t[(i, 0)])] = 1;
+                }
+                res
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_validates_targets() {
+        let targets: Targets<2, 3> = serde_json::from_str(r#"{"onehot":[[0,0,1],[1,0,0]]}"#).unwrap();
+        assert_eq!(targets.class_ids(), Tensor::from_data([[2], [0]]));
+        let categorical = Targets::<2, 3>::categorical(targets.class_ids());
+        assert_eq!(serde_json::to_string(&categorical).unwrap(), r#"{"categorical":[[2],[0]]}"#);
+        let err = serde_json::from_str::<Targets<2, 3>>(r#"{"categorical":[[2],[3]]}"#).unwrap_err();
+        assert!(err.to_string().starts_with("targets: class 3 of sample 1 is out of range for 3 classes"), "{}", err);
+        let err = serde_json::from_str::<Targets<2, 3>>(r#"{"onehot":[[0,0,1],[1,1,0]]}"#).unwrap_err();
+        assert!(err.to_string().starts_with("targets: row 1 is not onehot"), "{}", err);
+        assert!(serde_json::from_str::<Targets<2, 3>>(r#"{"categorical":[[2]]}"#).is_err());
+    }
+}
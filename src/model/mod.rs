@@ -0,0 +1,205 @@
+use crate::activator::Activator;
+use crate::activator::relu::ReLU;
+use crate::activator::softmax::Softmax;
+use crate::layer::{DenseLayer, Layer};
+use crate::optimizer::Optimizer;
+use crate::tensor::{ShapeError, Tensor};
+
+// Anything that can be a stage of a model: layers as well as activators.
+// N_INPUTS and N_OUTPUTS are the feature dimensions, the batch size is picked per call
+pub trait Module<const N_INPUTS: usize, const N_OUTPUTS: usize> {
+    fn forward<const BATCH_SIZE: usize>(&mut self, inputs: &Tensor<f32, BATCH_SIZE, N_INPUTS>) -> Tensor<f32, BATCH_SIZE, N_OUTPUTS>;
+    fn backward<const BATCH_SIZE: usize>(&mut self, dvalues: &Tensor<f32, BATCH_SIZE, N_OUTPUTS>) -> Result<Tensor<f32, BATCH_SIZE, N_INPUTS>, ShapeError>;
+    // Calls the visitor with every trainable layer in order, modules without parameters do nothing
+    fn visit_layers<V: LayerVisitor>(&mut self, _visitor: &mut V) {}
+    // Same as `visit_layers` for visitors that only read the layers, e.g. to save them
+    fn inspect_layers<V: LayerInspector>(&self, _inspector: &mut V) {}
+
+    // Runs one optimizer step over every trainable layer
+    fn update_params<O: Optimizer>(&mut self, optimizer: &mut O)
+    where
+        Self: Sized
+    {
+        optimizer.pre_update_params();
+        self.visit_layers(&mut OptimizerStep { optimizer: &*optimizer });
+        optimizer.post_update_params();
+    }
+}
+
+pub trait LayerVisitor {
+    fn visit_dense<const N_INPUTS: usize, const N_NEURONS: usize>(&mut self, layer: &mut DenseLayer<N_INPUTS, N_NEURONS>);
+}
+
+pub trait LayerInspector {
+    fn inspect_dense<const N_INPUTS: usize, const N_NEURONS: usize>(&mut self, layer: &DenseLayer<N_INPUTS, N_NEURONS>);
+}
+
+impl<const N_INPUTS: usize, const N_NEURONS: usize> Module<N_INPUTS, N_NEURONS> for DenseLayer<N_INPUTS, N_NEURONS> {
+    fn forward<const BATCH_SIZE: usize>(&mut self, inputs: &Tensor<f32, BATCH_SIZE, N_INPUTS>) -> Tensor<f32, BATCH_SIZE, N_NEURONS> {
+        Layer::forward(self, inputs)
+    }
+
+    fn backward<const BATCH_SIZE: usize>(&mut self, dvalues: &Tensor<f32, BATCH_SIZE, N_NEURONS>) -> Result<Tensor<f32, BATCH_SIZE, N_INPUTS>, ShapeError> {
+        Layer::backward(self, dvalues)
+    }
+
+    fn visit_layers<V: LayerVisitor>(&mut self, visitor: &mut V) {
+        visitor.visit_dense(self);
+    }
+
+    fn inspect_layers<V: LayerInspector>(&self, inspector: &mut V) {
+        inspector.inspect_dense(self);
+    }
+}
+
+// Activators have no parameters, so as modules they only forward to the Activator impl
+macro_rules! activator_module {
+    ($($activator:ident),*) => {$(
+        impl<const N_INPUTS: usize> Module<N_INPUTS, N_INPUTS> for $activator<N_INPUTS> {
+            fn forward<const BATCH_SIZE: usize>(&mut self, inputs: &Tensor<f32, BATCH_SIZE, N_INPUTS>) -> Tensor<f32, BATCH_SIZE, N_INPUTS> {
+                Activator::forward(self, inputs)
+            }
+
+            fn backward<const BATCH_SIZE: usize>(&mut self, dvalues: &Tensor<f32, BATCH_SIZE, N_INPUTS>) -> Result<Tensor<f32, BATCH_SIZE, N_INPUTS>, ShapeError> {
+                Activator::backward(self, dvalues)
+            }
+        }
+    )*};
+}
+
+activator_module!(ReLU, Softmax);
+
+// Two modules run one after the other. Longer models are built by nesting with `then`:
+//
+//     let model = Sequential::new(DenseLayer::<2, 64>::new(), ReLU::new())
+//         .then(DenseLayer::<64, 3>::new());
+//
+// N_HIDDEN is the output dimension of `first` and the input dimension of `second`,
+// a model whose stages don't fit together doesn't compile
+pub struct Sequential<A, B, const N_HIDDEN: usize> {
+    first: A,
+    second: B
+}
+
+impl<A, B, const N_HIDDEN: usize> Sequential<A, B, N_HIDDEN> {
+    pub fn new<const N_INPUTS: usize, const N_OUTPUTS: usize>(first: A, second: B) -> Self
+    where
+        A: Module<N_INPUTS, N_HIDDEN>,
+        B: Module<N_HIDDEN, N_OUTPUTS>
+    {
+        Sequential { first, second }
+    }
+
+    // Appends `next` after the current last stage
+    pub fn then<C, const N_INPUTS: usize, const N_OUTPUTS: usize, const N_NEXT: usize>(self, next: C) -> Sequential<Self, C, N_OUTPUTS>
+    where
+        Self: Module<N_INPUTS, N_OUTPUTS>,
+        C: Module<N_OUTPUTS, N_NEXT>
+    {
+        Sequential { first: self, second: next }
+    }
+
+    pub fn first(&self) -> &A {
+        &self.first
+    }
+
+    pub fn second(&self) -> &B {
+        &self.second
+    }
+}
+
+impl<A, B, const N_INPUTS: usize, const N_HIDDEN: usize, const N_OUTPUTS: usize> Module<N_INPUTS, N_OUTPUTS> for Sequential<A, B, N_HIDDEN>
+where
+    A: Module<N_INPUTS, N_HIDDEN>,
+    B: Module<N_HIDDEN, N_OUTPUTS>
+{
+    fn forward<const BATCH_SIZE: usize>(&mut self, inputs: &Tensor<f32, BATCH_SIZE, N_INPUTS>) -> Tensor<f32, BATCH_SIZE, N_OUTPUTS> {
+        let hidden = self.first.forward(inputs);
+        self.second.forward(&hidden)
+    }
+
+    fn backward<const BATCH_SIZE: usize>(&mut self, dvalues: &Tensor<f32, BATCH_SIZE, N_OUTPUTS>) -> Result<Tensor<f32, BATCH_SIZE, N_INPUTS>, ShapeError> {
+        let dhidden = self.second.backward(dvalues)?;
+        self.first.backward(&dhidden)
+    }
+
+    fn visit_layers<V: LayerVisitor>(&mut self, visitor: &mut V) {
+        self.first.visit_layers(visitor);
+        self.second.visit_layers(visitor);
+    }
+
+    fn inspect_layers<V: LayerInspector>(&self, inspector: &mut V) {
+        self.first.inspect_layers(inspector);
+        self.second.inspect_layers(inspector);
+    }
+}
+
+struct OptimizerStep<'a, O> {
+    optimizer: &'a O
+}
+
+impl<O: Optimizer> LayerVisitor for OptimizerStep<'_, O> {
+    fn visit_dense<const N_INPUTS: usize, const N_NEURONS: usize>(&mut self, layer: &mut DenseLayer<N_INPUTS, N_NEURONS>) {
+        self.optimizer.update_params(layer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dense<const N_INPUTS: usize, const N_NEURONS: usize>(offset: f32) -> DenseLayer<N_INPUTS, N_NEURONS> {
+        let mut weights: Tensor<f32, N_INPUTS, N_NEURONS> = Tensor::new();
+        for i in 0..N_INPUTS {
+            for j in 0..N_NEURONS {
+                weights[(i, j)] = offset + (i as f32 - j as f32) * 0.5;
+            }
+        }
+        DenseLayer::from_params(weights, Tensor::fill(offset))
+    }
+
+    #[test]
+    fn chains_forward_and_backward() {
+        let inputs: Tensor<f32, 2, 2> = Tensor::from_data([[1.0, -2.0], [0.5, 3.0]]);
+        let dvalues: Tensor<f32, 2, 3> = Tensor::from_data([[1.0, 0.0, -1.0], [0.5, 2.0, 0.0]]);
+        let mut model = Sequential::new(dense::<2, 4>(0.1), ReLU::new()).then(dense::<4, 3>(-0.2));
+        let outputs = model.forward(&inputs);
+        let dinputs = model.backward(&dvalues).unwrap();
+
+        let (mut first, mut relu, mut last) = (dense::<2, 4>(0.1), ReLU::<4>::new(), dense::<4, 3>(-0.2));
+        let hidden = Activator::forward(&mut relu, &Layer::forward(&mut first, &inputs));
+        assert_eq!(outputs, Layer::forward(&mut last, &hidden));
+        let dhidden = Activator::backward(&mut relu, &Layer::backward(&mut last, &dvalues).unwrap()).unwrap();
+        assert_eq!(dinputs, Layer::backward(&mut first, &dhidden).unwrap());
+        assert_eq!(model.first().first().dweights(), first.dweights());
+        assert_eq!(model.second().dbiases(), last.dbiases());
+    }
+
+    struct Shapes(Vec<(usize, usize)>);
+
+    impl LayerVisitor for Shapes {
+        fn visit_dense<const N_INPUTS: usize, const N_NEURONS: usize>(&mut self, _layer: &mut DenseLayer<N_INPUTS, N_NEURONS>) {
+            self.0.push((N_INPUTS, N_NEURONS));
+        }
+    }
+
+    impl LayerInspector for Shapes {
+        fn inspect_dense<const N_INPUTS: usize, const N_NEURONS: usize>(&mut self, _layer: &DenseLayer<N_INPUTS, N_NEURONS>) {
+            self.0.push((N_INPUTS, N_NEURONS));
+        }
+    }
+
+    #[test]
+    fn visits_layers_in_order() {
+        let mut model = Sequential::new(dense::<2, 4>(0.0), ReLU::new())
+            .then(dense::<4, 5>(0.0))
+            .then(Softmax::new())
+            .then(dense::<5, 3>(0.0));
+        let mut visited = Shapes(Vec::new());
+        model.visit_layers(&mut visited);
+        assert_eq!(visited.0, vec![(2, 4), (4, 5), (5, 3)]);
+        let mut inspected = Shapes(Vec::new());
+        model.inspect_layers(&mut inspected);
+        assert_eq!(inspected.0, visited.0);
+    }
+}
@@ -0,0 +1,62 @@
+use crate::layer::DenseLayer;
+
+pub struct Adagrad {
+    learning_rate: f32,
+    current_learning_rate: f32,
+    decay: f32,
+    epsilon: f32,
+    iterations: usize,
+    id: usize
+}
+
+impl Adagrad {
+    pub fn new(learning_rate: f32, decay: f32, epsilon: f32) -> Self {
+        Adagrad {
+            learning_rate,
+            current_learning_rate: learning_rate,
+            decay,
+            epsilon,
+            iterations: 0,
+            id: super::next_id()
+        }
+    }
+
+    pub fn current_learning_rate(&self) -> f32 {
+        self.current_learning_rate
+    }
+}
+
+impl Default for Adagrad {
+    fn default() -> Self {
+        Adagrad::new(1.0, 0.0, 1e-7)
+    }
+}
+
+impl super::Optimizer for Adagrad {
+    fn pre_update_params(&mut self) {
+        if self.decay != 0.0 {
+            self.current_learning_rate = super::decayed_learning_rate(self.learning_rate, self.decay, self.iterations);
+        }
+    }
+
+    fn update_params<const N_INPUTS: usize, const N_NEURONS: usize>(&self, layer: &mut DenseLayer<N_INPUTS, N_NEURONS>) {
+        layer.claim_optimizer_state(self.id);
+        let learning_rate = self.current_learning_rate;
+        let epsilon = self.epsilon;
+        // The cache accumulates every squared gradient seen so far
+        layer.weight_cache.apply_with(&layer.dweights, |(c, d)| c + d * d);
+        layer.bias_cache.apply_with(&layer.dbiases, |(c, d)| c + d * d);
+        for i in 0..N_INPUTS {
+            for j in 0..N_NEURONS {
+                layer.weights[(i, j)] -= learning_rate * layer.dweights[(i, j)] / (layer.weight_cache[(i, j)].sqrt() + epsilon);
+            }
+        }
+        for j in 0..N_NEURONS {
+            layer.biases[(0, j)] -= learning_rate * layer.dbiases[(0, j)] / (layer.bias_cache[(0, j)].sqrt() + epsilon);
+        }
+    }
+
+    fn post_update_params(&mut self) {
+        self.iterations += 1;
+    }
+}
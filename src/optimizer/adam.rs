@@ -0,0 +1,119 @@
+use crate::layer::DenseLayer;
+
+pub struct Adam {
+    learning_rate: f32,
+    current_learning_rate: f32,
+    decay: f32,
+    epsilon: f32,
+    beta_1: f32,
+    beta_2: f32,
+    iterations: usize,
+    id: usize
+}
+
+// Adam with weight decay applied directly to the weights instead of through the gradients
+pub struct AdamW {
+    adam: Adam,
+    weight_decay: f32
+}
+
+impl Adam {
+    // beta_1 and beta_2 are the decay rates of the gradient and squared gradient moving averages
+    pub fn new(learning_rate: f32, decay: f32, epsilon: f32, beta_1: f32, beta_2: f32) -> Self {
+        Adam {
+            learning_rate,
+            current_learning_rate: learning_rate,
+            decay,
+            epsilon,
+            beta_1,
+            beta_2,
+            iterations: 0,
+            id: super::next_id()
+        }
+    }
+
+    pub fn current_learning_rate(&self) -> f32 {
+        self.current_learning_rate
+    }
+}
+
+impl Default for Adam {
+    fn default() -> Self {
+        Adam::new(0.001, 0.0, 1e-7, 0.9, 0.999)
+    }
+}
+
+impl super::Optimizer for Adam {
+    fn pre_update_params(&mut self) {
+        if self.decay != 0.0 {
+            self.current_learning_rate = super::decayed_learning_rate(self.learning_rate, self.decay, self.iterations);
+        }
+    }
+
+    fn update_params<const N_INPUTS: usize, const N_NEURONS: usize>(&self, layer: &mut DenseLayer<N_INPUTS, N_NEURONS>) {
+        layer.claim_optimizer_state(self.id);
+        let learning_rate = self.current_learning_rate;
+        let epsilon = self.epsilon;
+        let (beta_1, beta_2) = (self.beta_1, self.beta_2);
+        layer.weight_momentums.apply_with(&layer.dweights, |(m, d)| beta_1 * m + (1.0 - beta_1) * d);
+        layer.bias_momentums.apply_with(&layer.dbiases, |(m, d)| beta_1 * m + (1.0 - beta_1) * d);
+        layer.weight_cache.apply_with(&layer.dweights, |(c, d)| beta_2 * c + (1.0 - beta_2) * d * d);
+        layer.bias_cache.apply_with(&layer.dbiases, |(c, d)| beta_2 * c + (1.0 - beta_2) * d * d);
+        // Both moving averages start at zero, so they are biased towards it in the first steps
+        let step = self.iterations as i32 + 1;
+        let momentum_correction = 1.0 - beta_1.powi(step);
+        let cache_correction = 1.0 - beta_2.powi(step);
+        for i in 0..N_INPUTS {
+            for j in 0..N_NEURONS {
+                let momentum = layer.weight_momentums[(i, j)] / momentum_correction;
+                let cache = layer.weight_cache[(i, j)] / cache_correction;
+                layer.weights[(i, j)] -= learning_rate * momentum / (cache.sqrt() + epsilon);
+            }
+        }
+        for j in 0..N_NEURONS {
+            let momentum = layer.bias_momentums[(0, j)] / momentum_correction;
+            let cache = layer.bias_cache[(0, j)] / cache_correction;
+            layer.biases[(0, j)] -= learning_rate * momentum / (cache.sqrt() + epsilon);
+        }
+    }
+
+    fn post_update_params(&mut self) {
+        self.iterations += 1;
+    }
+}
+
+impl AdamW {
+    pub fn new(learning_rate: f32, decay: f32, epsilon: f32, beta_1: f32, beta_2: f32, weight_decay: f32) -> Self {
+        AdamW {
+            adam: Adam::new(learning_rate, decay, epsilon, beta_1, beta_2),
+            weight_decay
+        }
+    }
+
+    pub fn current_learning_rate(&self) -> f32 {
+        self.adam.current_learning_rate
+    }
+}
+
+impl Default for AdamW {
+    fn default() -> Self {
+        AdamW::new(0.001, 0.0, 1e-7, 0.9, 0.999, 0.01)
+    }
+}
+
+impl super::Optimizer for AdamW {
+    fn pre_update_params(&mut self) {
+        self.adam.pre_update_params();
+    }
+
+    fn update_params<const N_INPUTS: usize, const N_NEURONS: usize>(&self, layer: &mut DenseLayer<N_INPUTS, N_NEURONS>) {
+        // Decoupled weight decay, biases are not decayed
+        let shrink = 1.0 - self.adam.current_learning_rate * self.weight_decay;
+        layer.weights.apply(|w| w * shrink);
+        self.adam.update_params(layer);
+    }
+
+    fn post_update_params(&mut self) {
+        self.adam.post_update_params();
+    }
+}
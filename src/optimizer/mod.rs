@@ -0,0 +1,147 @@
+pub mod sgd;
+pub mod adagrad;
+pub mod rmsprop;
+pub mod adam;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::layer::DenseLayer;
+
+pub trait Optimizer {
+    // Called once per step before any parameters are updated
+    fn pre_update_params(&mut self);
+    fn update_params<const N_INPUTS: usize, const N_NEURONS: usize>(&self, layer: &mut DenseLayer<N_INPUTS, N_NEURONS>);
+    // Called once per step after all parameters are updated
+    fn post_update_params(&mut self);
+}
+
+// Every optimizer gets its own id so that a layer can tell when another optimizer takes over
+// and start that one from fresh momentums and caches instead of reusing the old state
+fn next_id() -> usize {
+    static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+// Learning rate after 1 / (1 + decay * step) decay
+fn decayed_learning_rate(learning_rate: f32, decay: f32, iterations: usize) -> f32 {
+    learning_rate * (1.0 / (1.0 + decay * iterations as f32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::adagrad::Adagrad;
+    use super::adam::{Adam, AdamW};
+    use super::rmsprop::RMSprop;
+    use super::sgd::SGD;
+    use super::*;
+    use crate::model::Module;
+    use crate::tensor::{Tensor, assert_close};
+
+    // One input and two neurons with fixed gradients, so every step can be worked out by hand
+    fn layer() -> DenseLayer<1, 2> {
+        let mut layer = DenseLayer::from_params(Tensor::from_data([[1.0, -2.0]]), Tensor::from_data([[0.5, 0.0]]));
+        layer.dweights = Tensor::from_data([[0.2, -0.4]]);
+        layer.dbiases = Tensor::from_data([[1.0, -1.0]]);
+        layer
+    }
+
+    #[test]
+    fn sgd_step() {
+        let mut layer = layer();
+        let mut optimizer = SGD::new(0.1, 0.0, 0.0);
+        layer.update_params(&mut optimizer);
+        assert_close(layer.weights(), [[0.98, -1.96]]);
+        assert_close(layer.biases(), [[0.4, 0.1]]);
+        layer.update_params(&mut optimizer);
+        assert_close(layer.weights(), [[0.96, -1.92]]);
+    }
+
+    #[test]
+    fn sgd_momentum_step() {
+        // velocity = 0.9 * velocity - lr * d, so the second step is 1.9 times the first
+        let mut layer = layer();
+        let mut optimizer = SGD::new(0.1, 0.0, 0.9);
+        layer.update_params(&mut optimizer);
+        assert_close(layer.weights(), [[0.98, -1.96]]);
+        assert_close(layer.biases(), [[0.4, 0.1]]);
+        layer.update_params(&mut optimizer);
+        assert_close(layer.weights(), [[0.942, -1.884]]);
+        assert_close(layer.biases(), [[0.21, 0.29]]);
+    }
+
+    #[test]
+    fn sgd_decay() {
+        // lr / (1 + decay * t) with t the number of steps taken before
+        let mut layer = layer();
+        let mut optimizer = SGD::new(1.0, 0.5, 0.0);
+        let mut expected = 1.0;
+        for lr in [1.0, 1.0 / 1.5, 0.5] {
+            layer.update_params(&mut optimizer);
+            assert!((optimizer.current_learning_rate() - lr).abs() < 1e-6);
+            expected -= lr * 0.2;
+            assert_close(layer.weights(), [[expected, -2.0 + 2.0 * (1.0 - expected)]]);
+        }
+    }
+
+    #[test]
+    fn adagrad_step() {
+        // The cache holds the sum of squared gradients, so every step is lr * d / sqrt(steps * d^2)
+        let mut layer = layer();
+        let mut optimizer = Adagrad::new(0.1, 0.0, 1e-7);
+        layer.update_params(&mut optimizer);
+        assert_close(layer.weights(), [[0.9, -1.9]]);
+        assert_close(layer.biases(), [[0.4, 0.1]]);
+        layer.update_params(&mut optimizer);
+        assert_close(layer.weights(), [[0.9 - 0.1 / 2f32.sqrt(), -1.9 + 0.1 / 2f32.sqrt()]]);
+    }
+
+    #[test]
+    fn rmsprop_step() {
+        // cache = 0.1 * d^2 after one step and 0.19 * d^2 after two
+        let mut layer = layer();
+        let mut optimizer = RMSprop::new(0.01, 0.0, 1e-7, 0.9);
+        layer.update_params(&mut optimizer);
+        let first = 0.01 / 0.1f32.sqrt();
+        assert_close(layer.weights(), [[1.0 - first, -2.0 + first]]);
+        assert_close(layer.biases(), [[0.5 - first, first]]);
+        layer.update_params(&mut optimizer);
+        let second = 0.01 / 0.19f32.sqrt();
+        assert_close(layer.weights(), [[1.0 - first - second, -2.0 + first + second]]);
+    }
+
+    #[test]
+    fn adam_bias_correction() {
+        // With a constant gradient the corrected averages are exactly d and d^2, so every step
+        // moves by lr. Uncorrected, the first step would be lr * 0.1 / sqrt(0.001) = 3.16 * lr
+        let mut layer = layer();
+        let mut optimizer = Adam::new(0.01, 0.0, 1e-7, 0.9, 0.999);
+        layer.update_params(&mut optimizer);
+        assert_close(layer.weights(), [[0.99, -1.99]]);
+        assert_close(layer.biases(), [[0.49, 0.01]]);
+        layer.update_params(&mut optimizer);
+        assert_close(layer.weights(), [[0.98, -1.98]]);
+        assert_close(layer.biases(), [[0.48, 0.02]]);
+    }
+
+    #[test]
+    fn adamw_decays_weights_only() {
+        // Weights shrink by 1 - lr * weight_decay before the Adam step, biases only take the Adam step
+        let mut layer = layer();
+        let mut optimizer = AdamW::new(0.01, 0.0, 1e-7, 0.9, 0.999, 0.5);
+        layer.update_params(&mut optimizer);
+        assert_close(layer.weights(), [[0.995 - 0.01, -1.99 + 0.01]]);
+        assert_close(layer.biases(), [[0.49, 0.01]]);
+    }
+
+    #[test]
+    fn switching_optimizers_resets_state() {
+        let mut layer = layer();
+        layer.update_params(&mut SGD::new(0.1, 0.0, 0.9));
+        layer.update_params(&mut Adagrad::new(0.1, 0.0, 1e-7));
+        assert_eq!(layer.weight_momentums, Tensor::new());
+        // A fresh Adam takes the same first step as on a new layer despite the SGD velocities
+        let before = layer.weights().clone();
+        layer.update_params(&mut Adam::new(0.01, 0.0, 1e-7, 0.9, 0.999));
+        assert_close(layer.weights(), [[before[(0, 0)] - 0.01, before[(0, 1)] + 0.01]]);
+    }
+}
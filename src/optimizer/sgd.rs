@@ -0,0 +1,62 @@
+use crate::layer::DenseLayer;
+
+pub struct SGD {
+    learning_rate: f32,
+    current_learning_rate: f32,
+    decay: f32,
+    momentum: f32,
+    iterations: usize,
+    id: usize
+}
+
+impl SGD {
+    // Learning rate decays as 1 / (1 + decay * step), a momentum of 0.0 gives plain SGD
+    pub fn new(learning_rate: f32, decay: f32, momentum: f32) -> Self {
+        SGD {
+            learning_rate,
+            current_learning_rate: learning_rate,
+            decay,
+            momentum,
+            iterations: 0,
+            id: super::next_id()
+        }
+    }
+
+    pub fn current_learning_rate(&self) -> f32 {
+        self.current_learning_rate
+    }
+}
+
+impl Default for SGD {
+    fn default() -> Self {
+        SGD::new(1.0, 0.0, 0.0)
+    }
+}
+
+impl super::Optimizer for SGD {
+    fn pre_update_params(&mut self) {
+        if self.decay != 0.0 {
+            self.current_learning_rate = super::decayed_learning_rate(self.learning_rate, self.decay, self.iterations);
+        }
+    }
+
+    fn update_params<const N_INPUTS: usize, const N_NEURONS: usize>(&self, layer: &mut DenseLayer<N_INPUTS, N_NEURONS>) {
+        layer.claim_optimizer_state(self.id);
+        let learning_rate = self.current_learning_rate;
+        let momentum = self.momentum;
+        if momentum != 0.0 {
+            // Velocities are updated and applied in place, nothing is allocated per step
+            layer.weight_momentums.apply_with(&layer.dweights, |(m, d)| momentum * m - learning_rate * d);
+            layer.bias_momentums.apply_with(&layer.dbiases, |(m, d)| momentum * m - learning_rate * d);
+            layer.weights.apply_with(&layer.weight_momentums, |(w, m)| w + m);
+            layer.biases.apply_with(&layer.bias_momentums, |(b, m)| b + m);
+        } else {
+            layer.weights.apply_with(&layer.dweights, |(w, d)| w - learning_rate * d);
+            layer.biases.apply_with(&layer.dbiases, |(b, d)| b - learning_rate * d);
+        }
+    }
+
+    fn post_update_params(&mut self) {
+        self.iterations += 1;
+    }
+}
@@ -0,0 +1,92 @@
+pub mod model_file;
+pub mod npy;
+pub mod safetensors;
+
+use std::fmt;
+
+use crate::layer::DenseLayer;
+use crate::model::{LayerInspector, LayerVisitor, Module};
+use crate::tensor::{DynTensor, Tensor};
+
+// The trainable parameters of a model are its dense layers' weights and biases, listed
+// layer by layer in the order the model runs them: weights of layer 0, biases of layer 0, ...
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParamMismatch {
+    Count { expected: usize, found: usize },
+    Shape { index: usize, expected: (usize, usize), found: (usize, usize) }
+}
+
+impl fmt::Display for ParamMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamMismatch::Count { expected, found } => write!(f, "model has {} parameter tensors but {} were given", expected, found),
+            ParamMismatch::Shape { index, expected, found } => write!(f, "parameter {} should be {}x{} but is {}x{}", index, expected.0, expected.1, found.0, found.1)
+        }
+    }
+}
+
+impl std::error::Error for ParamMismatch {}
+
+struct CollectParams {
+    params: Vec<DynTensor<f32>>
+}
+
+impl LayerInspector for CollectParams {
+    fn inspect_dense<const N_INPUTS: usize, const N_NEURONS: usize>(&mut self, layer: &DenseLayer<N_INPUTS, N_NEURONS>) {
+        self.params.push(DynTensor::from(&layer.weights));
+        self.params.push(DynTensor::from(&layer.biases));
+    }
+}
+
+struct ParamShapes {
+    shapes: Vec<(usize, usize)>
+}
+
+impl LayerInspector for ParamShapes {
+    fn inspect_dense<const N_INPUTS: usize, const N_NEURONS: usize>(&mut self, _layer: &DenseLayer<N_INPUTS, N_NEURONS>) {
+        self.shapes.push((N_INPUTS, N_NEURONS));
+        self.shapes.push((1, N_NEURONS));
+    }
+}
+
+struct RestoreParams {
+    params: std::vec::IntoIter<DynTensor<f32>>
+}
+
+impl LayerVisitor for RestoreParams {
+    fn visit_dense<const N_INPUTS: usize, const N_NEURONS: usize>(&mut self, layer: &mut DenseLayer<N_INPUTS, N_NEURONS>) {
+        let weights = self.params.next().expect("parameter count was checked");
+        let biases = self.params.next().expect("parameter count was checked");
+        layer.weights = Tensor::try_from(weights).expect("parameter shapes were checked");
+        layer.biases = Tensor::try_from(biases).expect("parameter shapes were checked");
+    }
+}
+
+pub fn collect_params<M: Module<N_INPUTS, N_OUTPUTS>, const N_INPUTS: usize, const N_OUTPUTS: usize>(model: &M) -> Vec<DynTensor<f32>> {
+    let mut collector = CollectParams { params: Vec::new() };
+    model.inspect_layers(&mut collector);
+    collector.params
+}
+
+// Shape of every parameter tensor of the model
+pub fn param_shapes<M: Module<N_INPUTS, N_OUTPUTS>, const N_INPUTS: usize, const N_OUTPUTS: usize>(model: &M) -> Vec<(usize, usize)> {
+    let mut shapes = ParamShapes { shapes: Vec::new() };
+    model.inspect_layers(&mut shapes);
+    shapes.shapes
+}
+
+// Replaces the model's parameters. Every shape is checked first so on error the model is left unchanged
+pub fn restore_params<M: Module<N_INPUTS, N_OUTPUTS>, const N_INPUTS: usize, const N_OUTPUTS: usize>(model: &mut M, params: Vec<DynTensor<f32>>) -> Result<(), ParamMismatch> {
+    let shapes = param_shapes(model);
+    if shapes.len() != params.len() {
+        return Err(ParamMismatch::Count { expected: shapes.len(), found: params.len() })
+    }
+    for (index, (&expected, param)) in shapes.iter().zip(params.iter()).enumerate() {
+        if param.shape() != expected {
+            return Err(ParamMismatch::Shape { index, expected, found: param.shape() })
+        }
+    }
+    model.visit_layers(&mut RestoreParams { params: params.into_iter() });
+    Ok(())
+}
@@ -0,0 +1,229 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use crate::model::Module;
+use crate::tensor::DynTensor;
+
+use super::{ParamMismatch, collect_params, restore_params};
+
+// Binary model file, all numbers little-endian:
+//
+//     magic    b"RSAI"
+//     version  u32
+//     layers   u32
+//     then per layer: kind u8 (0 = dense) followed by its weights and biases, every tensor as
+//     dtype u8 (0 = f32), rank u8 (2), the dims as u32, then the elements in row-major order
+//
+// Version 1 is the only version so far. Optimizer state is not saved
+
+const MAGIC: &[u8; 4] = b"RSAI";
+pub const VERSION: u32 = 1;
+
+const DENSE: u8 = 0;
+const F32: u8 = 0;
+
+#[derive(Debug)]
+pub enum ModelFileError {
+    Io(io::Error),
+    BadMagic([u8; 4]),
+    UnsupportedVersion(u32),
+    UnknownLayerKind(u8),
+    UnsupportedDtype(u8),
+    UnsupportedRank(u8),
+    // The byte size of a tensor overflows usize
+    TooLarge { rows: usize, cols: usize },
+    // Data ended before all the elements of a tensor were read
+    Truncated,
+    // The file doesn't fit the layers of the model it is loaded into
+    Params(ParamMismatch)
+}
+
+impl fmt::Display for ModelFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModelFileError::Io(err) => write!(f, "model file: {}", err),
+            ModelFileError::BadMagic(magic) => write!(f, "model file: bad magic number {:02x?}", magic),
+            ModelFileError::UnsupportedVersion(version) => write!(f, "model file: unsupported version {}, expected {}", version, VERSION),
+            ModelFileError::UnknownLayerKind(kind) => write!(f, "model file: unknown layer kind {}", kind),
+            ModelFileError::UnsupportedDtype(dtype) => write!(f, "model file: unsupported dtype {}", dtype),
+            ModelFileError::UnsupportedRank(rank) => write!(f, "model file: unsupported tensor rank {}", rank),
+            ModelFileError::TooLarge { rows, cols } => write!(f, "model file: {}x{} tensor is too large", rows, cols),
+            ModelFileError::Truncated => write!(f, "model file: truncated"),
+            ModelFileError::Params(err) => write!(f, "model file: {}", err)
+        }
+    }
+}
+
+impl std::error::Error for ModelFileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ModelFileError::Io(err) => Some(err),
+            ModelFileError::Params(err) => Some(err),
+            _ => None
+        }
+    }
+}
+
+impl From<io::Error> for ModelFileError {
+    fn from(err: io::Error) -> Self {
+        if err.kind() == io::ErrorKind::UnexpectedEof {
+            return ModelFileError::Truncated
+        }
+        ModelFileError::Io(err)
+    }
+}
+
+impl From<ParamMismatch> for ModelFileError {
+    fn from(err: ParamMismatch) -> Self {
+        ModelFileError::Params(err)
+    }
+}
+
+fn write_tensor<W: Write>(writer: &mut W, tensor: &DynTensor<f32>) -> io::Result<()> {
+    writer.write_all(&[F32, 2])?;
+    writer.write_all(&(tensor.rows() as u32).to_le_bytes())?;
+    writer.write_all(&(tensor.cols() as u32).to_le_bytes())?;
+    for el in tensor.data() {
+        writer.write_all(&el.to_le_bytes())?;
+    }
+    Ok(())
+}
+
+fn read_u8<R: Read>(reader: &mut R) -> io::Result<u8> {
+    let mut buf = [0u8; 1];
+    reader.read_exact(&mut buf)?;
+    Ok(buf[0])
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> io::Result<u32> {
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_tensor<R: Read>(reader: &mut R) -> Result<DynTensor<f32>, ModelFileError> {
+    let dtype = read_u8(reader)?;
+    if dtype != F32 {
+        return Err(ModelFileError::UnsupportedDtype(dtype))
+    }
+    let rank = read_u8(reader)?;
+    if rank != 2 {
+        return Err(ModelFileError::UnsupportedRank(rank))
+    }
+    let rows = read_u32(reader)? as usize;
+    let cols = read_u32(reader)? as usize;
+    let len = rows.checked_mul(cols)
+        .and_then(|len| len.checked_mul(size_of::<f32>()))
+        .ok_or(ModelFileError::TooLarge { rows, cols })?;
+    let mut bytes = Vec::new();
+    reader.by_ref().take(len as u64).read_to_end(&mut bytes)?;
+    if bytes.len() != len {
+        return Err(ModelFileError::Truncated)
+    }
+    let data = bytes.chunks_exact(4).map(|el| f32::from_le_bytes([el[0], el[1], el[2], el[3]])).collect();
+    Ok(DynTensor::from_vec(rows, cols, data).expect("data length matches the dims"))
+}
+
+pub fn write_model<W: Write, M: Module<N_INPUTS, N_OUTPUTS>, const N_INPUTS: usize, const N_OUTPUTS: usize>(writer: &mut W, model: &M) -> Result<(), ModelFileError> {
+    let params = collect_params(model);
+    writer.write_all(MAGIC)?;
+    writer.write_all(&VERSION.to_le_bytes())?;
+    writer.write_all(&((params.len() / 2) as u32).to_le_bytes())?;
+    for layer in params.chunks(2) {
+        writer.write_all(&[DENSE])?;
+        write_tensor(writer, &layer[0])?;
+        write_tensor(writer, &layer[1])?;
+    }
+    Ok(())
+}
+
+// Restores the weights and biases of `model` from a file written by `write_model` for a model
+// with the same layers. The model is only changed if the whole file is valid
+pub fn read_model<R: Read, M: Module<N_INPUTS, N_OUTPUTS>, const N_INPUTS: usize, const N_OUTPUTS: usize>(reader: &mut R, model: &mut M) -> Result<(), ModelFileError> {
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(ModelFileError::BadMagic(magic))
+    }
+    let version = read_u32(reader)?;
+    if version != VERSION {
+        return Err(ModelFileError::UnsupportedVersion(version))
+    }
+    let n_layers = read_u32(reader)? as usize;
+    let mut params = Vec::new();
+    for _ in 0..n_layers {
+        let kind = read_u8(reader)?;
+        if kind != DENSE {
+            return Err(ModelFileError::UnknownLayerKind(kind))
+        }
+        params.push(read_tensor(reader)?);
+        params.push(read_tensor(reader)?);
+    }
+    restore_params(model, params)?;
+    Ok(())
+}
+
+pub fn save_model<M: Module<N_INPUTS, N_OUTPUTS>, const N_INPUTS: usize, const N_OUTPUTS: usize>(path: impl AsRef<Path>, model: &M) -> Result<(), ModelFileError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_model(&mut writer, model)?;
+    writer.flush()?;
+    Ok(())
+}
+
+pub fn load_model<M: Module<N_INPUTS, N_OUTPUTS>, const N_INPUTS: usize, const N_OUTPUTS: usize>(path: impl AsRef<Path>, model: &mut M) -> Result<(), ModelFileError> {
+    read_model(&mut BufReader::new(File::open(path)?), model)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activator::relu::ReLU;
+    use crate::layer::{DenseLayer, Layer};
+    use crate::model::Sequential;
+
+    #[test]
+    fn round_trip() {
+        let model = Sequential::new(DenseLayer::<2, 4>::new(), ReLU::new()).then(DenseLayer::<4, 3>::new());
+        let mut bytes = Vec::new();
+        write_model(&mut bytes, &model).unwrap();
+
+        let mut restored = Sequential::new(DenseLayer::<2, 4>::new(), ReLU::new()).then(DenseLayer::<4, 3>::new());
+        read_model(&mut bytes.as_slice(), &mut restored).unwrap();
+        assert_eq!(restored.first().first().weights(), model.first().first().weights());
+        assert_eq!(restored.second().biases(), model.second().biases());
+    }
+
+    #[test]
+    fn rejects_other_models() {
+        let mut model = Sequential::new(DenseLayer::<2, 4>::new(), ReLU::new()).then(DenseLayer::<4, 3>::new());
+        let mut bytes = Vec::new();
+        write_model(&mut bytes, &model).unwrap();
+
+        let mut wider = Sequential::new(DenseLayer::<2, 5>::new(), ReLU::new()).then(DenseLayer::<5, 3>::new());
+        let before = wider.first().first().weights().clone();
+        let err = read_model(&mut bytes.as_slice(), &mut wider).unwrap_err();
+        assert_eq!(err.to_string(), "model file: parameter 0 should be 2x5 but is 2x4");
+        assert_eq!(wider.first().first().weights(), &before);
+
+        let mut single = DenseLayer::<2, 4>::new();
+        assert!(matches!(read_model(&mut bytes.as_slice(), &mut single), Err(ModelFileError::Params(ParamMismatch::Count { expected: 2, found: 4 }))));
+        assert!(matches!(read_model(&mut &bytes[..bytes.len() - 1], &mut model), Err(ModelFileError::Truncated)));
+        bytes[4] = 2;
+        assert!(matches!(read_model(&mut bytes.as_slice(), &mut model), Err(ModelFileError::UnsupportedVersion(2))));
+    }
+
+    #[test]
+    fn rejects_oversized_tensors() {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend(VERSION.to_le_bytes());
+        bytes.extend(1u32.to_le_bytes());
+        bytes.extend([DENSE, F32, 2]);
+        bytes.extend(u32::MAX.to_le_bytes());
+        bytes.extend(u32::MAX.to_le_bytes());
+        let mut model = DenseLayer::<2, 4>::new();
+        let err = read_model(&mut bytes.as_slice(), &mut model).unwrap_err();
+        assert!(matches!(err, ModelFileError::TooLarge { .. }), "{}", err);
+    }
+}
@@ -0,0 +1,609 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use crate::tensor::{DynTensor, Tensor};
+
+// NumPy's .npy format (https://numpy.org/doc/stable/reference/generated/numpy.lib.format.html):
+// the magic string b"\x93NUMPY", a major and minor version byte, the header length (u16 for
+// version 1, u32 for versions 2 and 3), a Python dict literal like
+// {'descr': '<f4', 'fortran_order': False, 'shape': (3, 2), } and then the raw elements.
+// Only little-endian f32, f64, i64 and u8 arrays in C order are supported.
+//
+// An .npz file is a zip archive of .npy files, as written by np.savez. Archives from
+// np.savez_compressed use deflate and are not supported
+
+const MAGIC: &[u8; 6] = b"\x93NUMPY";
+
+#[derive(Debug)]
+pub enum NpyError {
+    Io(io::Error),
+    BadMagic,
+    UnsupportedVersion(u8, u8),
+    BadHeader(String),
+    UnsupportedDtype(String),
+    // The array holds another element type than the tensor it is read into
+    DtypeMismatch { expected: &'static str, found: String },
+    FortranOrder,
+    ShapeMismatch { expected: Vec<usize>, found: Vec<usize> },
+    Truncated,
+    BadArchive(String),
+    UnsupportedCompression { name: String, method: u16 },
+    ChecksumMismatch(String),
+    MissingArray(String)
+}
+
+impl fmt::Display for NpyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NpyError::Io(err) => write!(f, "npy: {}", err),
+            NpyError::BadMagic => write!(f, "npy: bad magic string, not an .npy file"),
+            NpyError::UnsupportedVersion(major, minor) => write!(f, "npy: unsupported format version {}.{}", major, minor),
+            NpyError::BadHeader(reason) => write!(f, "npy: bad header: {}", reason),
+            NpyError::UnsupportedDtype(descr) => write!(f, "npy: unsupported dtype {:?}, expected one of <f4, <f8, <i8, |u1", descr),
+            NpyError::DtypeMismatch { expected, found } => write!(f, "npy: expected dtype {} but the array is {}", expected, found),
+            NpyError::FortranOrder => write!(f, "npy: arrays in Fortran order are not supported"),
+            NpyError::ShapeMismatch { expected, found } => write!(f, "npy: expected shape {:?} but the array is {:?}", expected, found),
+            NpyError::Truncated => write!(f, "npy: truncated data"),
+            NpyError::BadArchive(reason) => write!(f, "npz: bad archive: {}", reason),
+            NpyError::UnsupportedCompression { name, method } => write!(f, "npz: {} uses compression method {}, only uncompressed archives (np.savez) are supported", name, method),
+            NpyError::ChecksumMismatch(name) => write!(f, "npz: checksum mismatch for {}", name),
+            NpyError::MissingArray(name) => write!(f, "npz: no array named {}", name)
+        }
+    }
+}
+
+impl std::error::Error for NpyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            NpyError::Io(err) => Some(err),
+            _ => None
+        }
+    }
+}
+
+impl From<io::Error> for NpyError {
+    fn from(err: io::Error) -> Self {
+        if err.kind() == io::ErrorKind::UnexpectedEof {
+            return NpyError::Truncated
+        }
+        NpyError::Io(err)
+    }
+}
+
+// Element types that can be stored in .npy files
+pub trait NpyElement: Copy + Default {
+    // NumPy dtype string as written to the header
+    const DESCR: &'static str;
+    const SIZE: usize;
+    fn from_le_bytes(bytes: &[u8]) -> Self;
+    fn write_le_bytes(&self, out: &mut Vec<u8>);
+
+    // Whether `descr` from a header means this type, some equivalent spellings are accepted
+    fn matches(descr: &str) -> bool {
+        descr == Self::DESCR
+    }
+}
+
+macro_rules! npy_element {
+    ($t:ty, $descr:expr, $($alias:expr),*) => {
+        impl NpyElement for $t {
+            const DESCR: &'static str = $descr;
+            const SIZE: usize = size_of::<$t>();
+
+            fn from_le_bytes(bytes: &[u8]) -> Self {
+                <$t>::from_le_bytes(bytes.try_into().expect("chunk has the size of the element"))
+            }
+
+            fn write_le_bytes(&self, out: &mut Vec<u8>) {
+                out.extend_from_slice(&self.to_le_bytes());
+            }
+
+            fn matches(descr: &str) -> bool {
+                descr == $descr $(|| descr == $alias)*
+            }
+        }
+    };
+}
+
+npy_element!(f32, "<f4", "=f4");
+npy_element!(f64, "<f8", "=f8");
+npy_element!(i64, "<i8", "=i8");
+npy_element!(u8, "|u1", "<u1", "=u1", "u1");
+
+struct Header {
+    descr: String,
+    fortran_order: bool,
+    shape: Vec<usize>
+}
+
+// Minimal parser for the Python literals that appear in .npy headers
+struct HeaderParser<'a> {
+    rest: &'a str
+}
+
+impl<'a> HeaderParser<'a> {
+    fn skip_ws(&mut self) {
+        self.rest = self.rest.trim_start();
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_ws();
+        match self.rest.strip_prefix(token) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), NpyError> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(NpyError::BadHeader(format!("expected {:?} at {:?}", token, self.rest)))
+        }
+    }
+
+    fn string(&mut self) -> Result<&'a str, NpyError> {
+        self.skip_ws();
+        let quote = match self.rest.chars().next() {
+            Some(quote @ ('\'' | '"')) => quote,
+            _ => return Err(NpyError::BadHeader(format!("expected a string at {:?}", self.rest)))
+        };
+        let end = self.rest[1..].find(quote).ok_or_else(|| NpyError::BadHeader("unterminated string".to_string()))?;
+        let value = &self.rest[1..end + 1];
+        self.rest = &self.rest[end + 2..];
+        Ok(value)
+    }
+
+    fn bool(&mut self) -> Result<bool, NpyError> {
+        if self.eat("True") {
+            Ok(true)
+        } else if self.eat("False") {
+            Ok(false)
+        } else {
+            Err(NpyError::BadHeader(format!("expected True or False at {:?}", self.rest)))
+        }
+    }
+
+    // (), (3,) or (3, 2)
+    fn shape(&mut self) -> Result<Vec<usize>, NpyError> {
+        self.expect("(")?;
+        let mut shape = Vec::new();
+        while !self.eat(")") {
+            self.skip_ws();
+            let digits = self.rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(self.rest.len());
+            let dim = self.rest[..digits].parse().map_err(|_| NpyError::BadHeader(format!("bad dimension at {:?}", self.rest)))?;
+            shape.push(dim);
+            self.rest = &self.rest[digits..];
+            if !self.eat(",") {
+                self.expect(")")?;
+                break
+            }
+        }
+        Ok(shape)
+    }
+
+    fn header(&mut self) -> Result<Header, NpyError> {
+        let (mut descr, mut fortran_order, mut shape) = (None, None, None);
+        self.expect("{")?;
+        while !self.eat("}") {
+            let key = self.string()?;
+            self.expect(":")?;
+            match key {
+                "descr" => descr = Some(self.string()?.to_string()),
+                "fortran_order" => fortran_order = Some(self.bool()?),
+                "shape" => shape = Some(self.shape()?),
+                _ => return Err(NpyError::BadHeader(format!("unknown key {:?}", key)))
+            }
+            if !self.eat(",") {
+                self.expect("}")?;
+                break
+            }
+        }
+        let missing = |key: &str| NpyError::BadHeader(format!("missing key {:?}", key));
+        Ok(Header {
+            descr: descr.ok_or_else(|| missing("descr"))?,
+            fortran_order: fortran_order.ok_or_else(|| missing("fortran_order"))?,
+            shape: shape.ok_or_else(|| missing("shape"))?
+        })
+    }
+}
+
+fn read_header<R: Read>(reader: &mut R) -> Result<Header, NpyError> {
+    let mut magic = [0u8; 8];
+    reader.read_exact(&mut magic)?;
+    if &magic[..6] != MAGIC {
+        return Err(NpyError::BadMagic)
+    }
+    let len = match magic[6] {
+        1 => {
+            let mut len = [0u8; 2];
+            reader.read_exact(&mut len)?;
+            u16::from_le_bytes(len) as usize
+        }
+        2 | 3 => {
+            let mut len = [0u8; 4];
+            reader.read_exact(&mut len)?;
+            u32::from_le_bytes(len) as usize
+        }
+        major => return Err(NpyError::UnsupportedVersion(major, magic[7]))
+    };
+    let mut header = Vec::new();
+    reader.by_ref().take(len as u64).read_to_end(&mut header)?;
+    if header.len() != len {
+        return Err(NpyError::Truncated)
+    }
+    let header = String::from_utf8(header).map_err(|_| NpyError::BadHeader("not valid text".to_string()))?;
+    HeaderParser { rest: &header }.header()
+}
+
+// A tensor of ROWS x COLS is stored with shape (ROWS, COLS). Arrays of shape (ROWS,)
+// can also be read into a single column tensor
+pub fn read_npy<R: Read, T: NpyElement, const ROWS: usize, const COLS: usize>(reader: &mut R) -> Result<Tensor<T, ROWS, COLS>, NpyError> {
+    let header = read_header(reader)?;
+    if !T::matches(&header.descr) {
+        if [f32::DESCR, f64::DESCR, i64::DESCR, u8::DESCR].iter().all(|descr| *descr != header.descr) {
+            return Err(NpyError::UnsupportedDtype(header.descr))
+        }
+        return Err(NpyError::DtypeMismatch { expected: T::DESCR, found: header.descr })
+    }
+    if header.fortran_order {
+        return Err(NpyError::FortranOrder)
+    }
+    if header.shape != [ROWS, COLS] && !(COLS == 1 && header.shape == [ROWS]) {
+        return Err(NpyError::ShapeMismatch { expected: vec![ROWS, COLS], found: header.shape })
+    }
+    let len = ROWS * COLS * T::SIZE;
+    let mut bytes = Vec::with_capacity(len);
+    reader.by_ref().take(len as u64).read_to_end(&mut bytes)?;
+    if bytes.len() != len {
+        return Err(NpyError::Truncated)
+    }
+    let data = bytes.chunks_exact(T::SIZE).map(T::from_le_bytes).collect();
+    let tensor = DynTensor::from_vec(ROWS, COLS, data).expect("data length matches the shape");
+    Ok(Tensor::try_from(tensor).expect("shape was checked"))
+}
+
+pub fn write_npy<W: Write, T: NpyElement, const ROWS: usize, const COLS: usize>(writer: &mut W, tensor: &Tensor<T, ROWS, COLS>) -> Result<(), NpyError> {
+    let mut header = format!("{{'descr': '{}', 'fortran_order': False, 'shape': ({}, {}), }}", T::DESCR, ROWS, COLS);
+    // Magic, version, length, header and the final newline together are a multiple of 64 bytes
+    let total = (MAGIC.len() + 4 + header.len() + 1).div_ceil(64) * 64;
+    header.extend(std::iter::repeat_n(' ', total - (MAGIC.len() + 4 + header.len() + 1)));
+    header.push('\n');
+    let mut bytes = Vec::with_capacity(total + ROWS * COLS * T::SIZE);
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&[1, 0]);
+    bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+    bytes.extend_from_slice(header.as_bytes());
+    for el in DynTensor::from(tensor).data() {
+        el.write_le_bytes(&mut bytes);
+    }
+    writer.write_all(&bytes)?;
+    Ok(())
+}
+
+pub fn load_npy<T: NpyElement, const ROWS: usize, const COLS: usize>(path: impl AsRef<Path>) -> Result<Tensor<T, ROWS, COLS>, NpyError> {
+    read_npy(&mut BufReader::new(File::open(path)?))
+}
+
+pub fn save_npy<T: NpyElement, const ROWS: usize, const COLS: usize>(path: impl AsRef<Path>, tensor: &Tensor<T, ROWS, COLS>) -> Result<(), NpyError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_npy(&mut writer, tensor)?;
+    writer.flush()?;
+    Ok(())
+}
+
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+// CRC-32 as used by zip
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0u32, |crc, &byte| CRC_TABLE[((crc ^ byte as u32) & 0xff) as usize] ^ (crc >> 8))
+}
+
+// Offsets and sizes come from the archive, so a corrupt one may point anywhere
+fn bytes_at(bytes: &[u8], offset: usize, len: usize) -> Result<&[u8], NpyError> {
+    offset.checked_add(len).and_then(|end| bytes.get(offset..end)).ok_or(NpyError::Truncated)
+}
+
+fn u16_at(bytes: &[u8], offset: usize) -> Result<u16, NpyError> {
+    bytes_at(bytes, offset, 2).map(|b| u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> Result<u32, NpyError> {
+    bytes_at(bytes, offset, 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn u64_at(bytes: &[u8], offset: usize) -> Result<u64, NpyError> {
+    bytes_at(bytes, offset, 8).map(|b| u64::from_le_bytes(b.try_into().expect("slice of 8 bytes")))
+}
+
+const LOCAL_HEADER: u32 = 0x0403_4b50;
+const CENTRAL_HEADER: u32 = 0x0201_4b50;
+const END_OF_CENTRAL_DIR: u32 = 0x0605_4b50;
+const ZIP64_EXTRA: u16 = 0x0001;
+// 1980-01-01 00:00 in MS-DOS format, the earliest date zip can store
+const DOS_DATE: u16 = (1 << 5) | 1;
+
+// Named arrays of an .npz archive, kept as the bytes of their .npy files.
+// Names are given without the .npy extension, np.savez(f, x=..., y=...) gives "x" and "y"
+#[derive(Debug, Clone, Default)]
+pub struct Npz {
+    arrays: Vec<(String, Vec<u8>)>
+}
+
+impl Npz {
+    pub fn new() -> Self {
+        Npz::default()
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.arrays.iter().map(|(name, _)| name.as_str())
+    }
+
+    pub fn get<T: NpyElement, const ROWS: usize, const COLS: usize>(&self, name: &str) -> Result<Tensor<T, ROWS, COLS>, NpyError> {
+        let (_, bytes) = self.arrays.iter().find(|(array, _)| array == name).ok_or_else(|| NpyError::MissingArray(name.to_string()))?;
+        read_npy(&mut bytes.as_slice())
+    }
+
+    // Adds the tensor under `name`, replacing an array with the same name
+    pub fn insert<T: NpyElement, const ROWS: usize, const COLS: usize>(&mut self, name: &str, tensor: &Tensor<T, ROWS, COLS>) {
+        let mut bytes = Vec::new();
+        write_npy(&mut bytes, tensor).expect("writing to a Vec can't fail");
+        match self.arrays.iter_mut().find(|(array, _)| array == name) {
+            Some((_, array)) => *array = bytes,
+            None => self.arrays.push((name.to_string(), bytes))
+        }
+    }
+
+    pub fn read<R: Read>(reader: &mut R) -> Result<Self, NpyError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        Npz::from_bytes(&bytes)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, NpyError> {
+        // The end of central directory record is last, only followed by a comment of up to 64KiB
+        let min_start = bytes.len().saturating_sub(22 + u16::MAX as usize);
+        let end = (min_start..bytes.len().saturating_sub(21)).rev()
+            .find(|&offset| u32_at(bytes, offset).ok() == Some(END_OF_CENTRAL_DIR))
+            .ok_or_else(|| NpyError::BadArchive("no end of central directory".to_string()))?;
+        let n_entries = u16_at(bytes, end + 10)? as usize;
+        let mut offset = u32_at(bytes, end + 16)? as usize;
+
+        let mut npz = Npz::new();
+        for _ in 0..n_entries {
+            if u32_at(bytes, offset)? != CENTRAL_HEADER {
+                return Err(NpyError::BadArchive(format!("no central directory entry at {}", offset)))
+            }
+            let method = u16_at(bytes, offset + 10)?;
+            let crc = u32_at(bytes, offset + 16)?;
+            let mut size = u32_at(bytes, offset + 24)? as u64;
+            let name_len = u16_at(bytes, offset + 28)? as usize;
+            let extra_len = u16_at(bytes, offset + 30)? as usize;
+            let comment_len = u16_at(bytes, offset + 32)? as usize;
+            let mut local = u32_at(bytes, offset + 42)? as u64;
+            let name = bytes_at(bytes, offset + 46, name_len)?;
+            let name = String::from_utf8_lossy(name).into_owned();
+
+            // Sizes and offsets that don't fit 32 bits are stored in the zip64 extra field, in this order
+            let extra = bytes_at(bytes, offset + 46 + name_len, extra_len)?;
+            let mut pos = 0;
+            while pos + 4 <= extra.len() {
+                let id = u16_at(extra, pos)?;
+                let len = u16_at(extra, pos + 2)? as usize;
+                if id == ZIP64_EXTRA {
+                    let mut field = pos + 4;
+                    if u32_at(bytes, offset + 24)? == u32::MAX {
+                        size = u64_at(extra, field)?;
+                        field += 8;
+                    }
+                    if u32_at(bytes, offset + 20)? == u32::MAX {
+                        field += 8;
+                    }
+                    if u32_at(bytes, offset + 42)? == u32::MAX {
+                        local = u64_at(extra, field)?;
+                    }
+                }
+                pos += 4 + len;
+            }
+            offset += 46 + name_len + extra_len + comment_len;
+
+            if method != 0 {
+                return Err(NpyError::UnsupportedCompression { name, method })
+            }
+            let (Ok(local), Ok(size)) = (usize::try_from(local), usize::try_from(size)) else {
+                return Err(NpyError::BadArchive(format!("{} is out of range", name)))
+            };
+            if u32_at(bytes, local)? != LOCAL_HEADER {
+                return Err(NpyError::BadArchive(format!("no local header for {}", name)))
+            }
+            let header_len = 30 + u16_at(bytes, local + 26)? as usize + u16_at(bytes, local + 28)? as usize;
+            let start = local.checked_add(header_len).ok_or(NpyError::Truncated)?;
+            let data = bytes_at(bytes, start, size)?;
+            if crc32(data) != crc {
+                return Err(NpyError::ChecksumMismatch(name))
+            }
+            let name = name.strip_suffix(".npy").unwrap_or(&name).to_string();
+            npz.arrays.push((name, data.to_vec()));
+        }
+        Ok(npz)
+    }
+
+    // Writes an uncompressed zip archive like np.savez
+    pub fn write<W: Write>(&self, writer: &mut W) -> Result<(), NpyError> {
+        let mut local = Vec::new();
+        let mut central = Vec::new();
+        for (name, data) in self.arrays.iter() {
+            let name = format!("{}.npy", name);
+            if data.len() > u32::MAX as usize || local.len() > u32::MAX as usize {
+                return Err(NpyError::BadArchive("archives over 4GiB are not supported".to_string()))
+            }
+            let crc = crc32(data);
+            let offset = local.len() as u32;
+            // Fields shared by the local header and the central directory entry: version needed,
+            // flags, method, time, date, crc, compressed size, size, name length
+            let mut common = Vec::new();
+            common.extend_from_slice(&20u16.to_le_bytes());
+            common.extend_from_slice(&0u16.to_le_bytes());
+            common.extend_from_slice(&0u16.to_le_bytes());
+            common.extend_from_slice(&0u16.to_le_bytes());
+            common.extend_from_slice(&DOS_DATE.to_le_bytes());
+            common.extend_from_slice(&crc.to_le_bytes());
+            common.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            common.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            common.extend_from_slice(&(name.len() as u16).to_le_bytes());
+
+            local.extend_from_slice(&LOCAL_HEADER.to_le_bytes());
+            local.extend_from_slice(&common);
+            local.extend_from_slice(&0u16.to_le_bytes());
+            local.extend_from_slice(name.as_bytes());
+            local.extend_from_slice(data);
+
+            central.extend_from_slice(&CENTRAL_HEADER.to_le_bytes());
+            central.extend_from_slice(&20u16.to_le_bytes());
+            central.extend_from_slice(&common);
+            // Extra length, comment length, disk, internal and external attributes
+            central.extend_from_slice(&[0; 2 + 2 + 2 + 2 + 4]);
+            central.extend_from_slice(&offset.to_le_bytes());
+            central.extend_from_slice(name.as_bytes());
+        }
+        let mut end = Vec::new();
+        end.extend_from_slice(&END_OF_CENTRAL_DIR.to_le_bytes());
+        end.extend_from_slice(&[0; 4]);
+        end.extend_from_slice(&(self.arrays.len() as u16).to_le_bytes());
+        end.extend_from_slice(&(self.arrays.len() as u16).to_le_bytes());
+        end.extend_from_slice(&(central.len() as u32).to_le_bytes());
+        end.extend_from_slice(&(local.len() as u32).to_le_bytes());
+        end.extend_from_slice(&0u16.to_le_bytes());
+
+        writer.write_all(&local)?;
+        writer.write_all(&central)?;
+        writer.write_all(&end)?;
+        Ok(())
+    }
+}
+
+pub fn load_npz(path: impl AsRef<Path>) -> Result<Npz, NpyError> {
+    Npz::read(&mut BufReader::new(File::open(path)?))
+}
+
+pub fn save_npz(path: impl AsRef<Path>, npz: &Npz) -> Result<(), NpyError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    npz.write(&mut writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // np.save(f, np.array([[1, 2], [3, 4], [5, 6]], dtype='<i8'))
+    fn numpy_i64() -> Vec<u8> {
+        let header = "{'descr': '<i8', 'fortran_order': False, 'shape': (3, 2), }";
+        let mut bytes = b"\x93NUMPY\x01\x00".to_vec();
+        let padded = format!("{:<width$}\n", header, width = 128 - 10 - 1);
+        bytes.extend((padded.len() as u16).to_le_bytes());
+        bytes.extend(padded.as_bytes());
+        for el in 1i64..=6 {
+            bytes.extend(el.to_le_bytes());
+        }
+        bytes
+    }
+
+    #[test]
+    fn reads_numpy_files() {
+        let tensor: Tensor<i64, 3, 2> = read_npy(&mut numpy_i64().as_slice()).unwrap();
+        assert_eq!(tensor, Tensor::from_data([[1, 2], [3, 4], [5, 6]]));
+
+        assert!(matches!(read_npy::<_, i64, 2, 3>(&mut numpy_i64().as_slice()), Err(NpyError::ShapeMismatch { .. })));
+        assert!(matches!(read_npy::<_, f32, 3, 2>(&mut numpy_i64().as_slice()), Err(NpyError::DtypeMismatch { expected: "<f4", .. })));
+        let mut fortran = numpy_i64();
+        let at = fortran.windows(5).position(|window| window == b"False").unwrap();
+        fortran[at..at + 5].copy_from_slice(b"True ");
+        assert!(matches!(read_npy::<_, i64, 3, 2>(&mut fortran.as_slice()), Err(NpyError::FortranOrder)));
+        let bytes = numpy_i64();
+        assert!(matches!(read_npy::<_, i64, 3, 2>(&mut &bytes[..bytes.len() - 1]), Err(NpyError::Truncated)));
+    }
+
+    #[test]
+    fn npy_round_trip() {
+        let tensor: Tensor<f32, 2, 3> = Tensor::from_data([[0.5, -1.0, 2.0], [3.0, 4.0, 1e-7]]);
+        let mut bytes = Vec::new();
+        write_npy(&mut bytes, &tensor).unwrap();
+        assert_eq!(bytes.len() % 64, 2 * 3 * size_of::<f32>());
+        assert_eq!(read_npy::<_, f32, 2, 3>(&mut bytes.as_slice()).unwrap(), tensor);
+
+        let labels: Tensor<u8, 3, 1> = Tensor::from_data([[0], [2], [1]]);
+        let mut bytes = Vec::new();
+        write_npy(&mut bytes, &labels).unwrap();
+        assert_eq!(read_npy::<_, u8, 3, 1>(&mut bytes.as_slice()).unwrap(), labels);
+    }
+
+    #[test]
+    fn npz_round_trip() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        let mut npz = Npz::new();
+        npz.insert("x", &Tensor::<f64, 2, 2>::from_data([[1.0, 2.0], [3.0, 4.0]]));
+        npz.insert("y", &Tensor::<i64, 2, 1>::from_data([[0], [1]]));
+        let mut bytes = Vec::new();
+        npz.write(&mut bytes).unwrap();
+
+        let npz = Npz::read(&mut bytes.as_slice()).unwrap();
+        assert_eq!(npz.names().collect::<Vec<_>>(), vec!["x", "y"]);
+        assert_eq!(npz.get::<f64, 2, 2>("x").unwrap(), Tensor::from_data([[1.0, 2.0], [3.0, 4.0]]));
+        assert_eq!(npz.get::<i64, 2, 1>("y").unwrap(), Tensor::from_data([[0], [1]]));
+        assert!(matches!(npz.get::<i64, 2, 1>("z"), Err(NpyError::MissingArray(_))));
+
+        // Last element of x, after the local header, the name and the 64 byte .npy header
+        bytes[30 + "x.npy".len() + 64 + 24] ^= 1;
+        assert!(matches!(Npz::read(&mut bytes.as_slice()), Err(NpyError::ChecksumMismatch(_))));
+    }
+
+    #[test]
+    fn rejects_out_of_range_zip64_fields() {
+        let mut npz = Npz::new();
+        npz.insert("x", &Tensor::<u8, 1, 1>::from_data([[7]]));
+        let mut archive = Vec::new();
+        npz.write(&mut archive).unwrap();
+        let end = archive.len() - 22;
+        let central = u32_at(&archive, end + 16).unwrap() as usize;
+
+        // Moves the size and the local header offset of the entry to a zip64 extra field
+        let zip64 = |size: u64, local: u64| {
+            let mut bytes = archive.clone();
+            bytes[central + 24..central + 28].copy_from_slice(&u32::MAX.to_le_bytes());
+            bytes[central + 30..central + 32].copy_from_slice(&20u16.to_le_bytes());
+            bytes[central + 42..central + 46].copy_from_slice(&u32::MAX.to_le_bytes());
+            let mut extra = ZIP64_EXTRA.to_le_bytes().to_vec();
+            extra.extend(16u16.to_le_bytes());
+            extra.extend(size.to_le_bytes());
+            extra.extend(local.to_le_bytes());
+            bytes.splice(central + 46 + "x.npy".len()..central + 46 + "x.npy".len(), extra);
+            Npz::read(&mut bytes.as_slice())
+        };
+        // The only entry is at the start of the archive, its data runs up to the central directory
+        let size = (central - 30 - "x.npy".len()) as u64;
+        assert_eq!(zip64(size, 0).unwrap().get::<u8, 1, 1>("x").unwrap(), Tensor::from_data([[7]]));
+        assert!(matches!(zip64(size, u64::MAX), Err(NpyError::BadArchive(_) | NpyError::Truncated)));
+        assert!(matches!(zip64(u64::MAX, 0), Err(NpyError::BadArchive(_) | NpyError::Truncated)));
+    }
+}
@@ -0,0 +1,516 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use crate::model::Module;
+use crate::tensor::DynTensor;
+
+use super::{collect_params, param_shapes, restore_params};
+
+// The safetensors format (https://github.com/huggingface/safetensors): a u64 little-endian
+// header length, a JSON header mapping every tensor name to its dtype, shape and
+// [begin, end) byte offsets into the data, then the raw little-endian data.
+//
+// Weights are stored like torch.nn.Linear stores them, with shape (neurons, inputs), so they
+// are transposed on write and read. Biases are stored as 1-D tensors of length neurons
+
+// Upper bound on the header length, real headers are a few KiB of JSON
+const MAX_HEADER_LEN: u64 = 100 * 1024 * 1024;
+
+#[derive(Debug)]
+pub enum SafetensorsError {
+    Io(io::Error),
+    BadHeader(String),
+    MissingTensor(String),
+    UnsupportedDtype { name: String, dtype: String },
+    ShapeMismatch { name: String, expected: Vec<usize>, found: Vec<usize> },
+    BadOffsets(String),
+    Truncated
+}
+
+impl fmt::Display for SafetensorsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SafetensorsError::Io(err) => write!(f, "safetensors: {}", err),
+            SafetensorsError::BadHeader(reason) => write!(f, "safetensors: bad header: {}", reason),
+            SafetensorsError::MissingTensor(name) => write!(f, "safetensors: no tensor named {}", name),
+            SafetensorsError::UnsupportedDtype { name, dtype } => write!(f, "safetensors: {} has dtype {}, only F32 is supported", name, dtype),
+            SafetensorsError::ShapeMismatch { name, expected, found } => write!(f, "safetensors: {} should have shape {:?} but has {:?}", name, expected, found),
+            SafetensorsError::BadOffsets(name) => write!(f, "safetensors: data offsets of {} don't match its shape or the file size", name),
+            SafetensorsError::Truncated => write!(f, "safetensors: truncated file")
+        }
+    }
+}
+
+impl std::error::Error for SafetensorsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SafetensorsError::Io(err) => Some(err),
+            _ => None
+        }
+    }
+}
+
+impl From<io::Error> for SafetensorsError {
+    fn from(err: io::Error) -> Self {
+        if err.kind() == io::ErrorKind::UnexpectedEof {
+            return SafetensorsError::Truncated
+        }
+        SafetensorsError::Io(err)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamKind {
+    Weights,
+    Biases
+}
+
+// "layers.0.weight", "layers.0.bias", "layers.1.weight", ... with layers counted from the input
+pub fn default_name(layer: usize, kind: ParamKind) -> String {
+    match kind {
+        ParamKind::Weights => format!("layers.{}.weight", layer),
+        ParamKind::Biases => format!("layers.{}.bias", layer)
+    }
+}
+
+fn kind_of(index: usize) -> ParamKind {
+    if index.is_multiple_of(2) { ParamKind::Weights } else { ParamKind::Biases }
+}
+
+// JSON string literal with the escapes the spec requires
+fn json_string(s: &str) -> String {
+    let mut res = String::with_capacity(s.len() + 2);
+    res.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => res.push_str("\\\""),
+            '\\' => res.push_str("\\\\"),
+            '\n' => res.push_str("\\n"),
+            '\r' => res.push_str("\\r"),
+            '\t' => res.push_str("\\t"),
+            c if (c as u32) < 0x20 => res.push_str(&format!("\\u{:04x}", c as u32)),
+            c => res.push(c)
+        }
+    }
+    res.push('"');
+    res
+}
+
+// Writes every dense layer's parameters, named by `names(layer index, kind)`
+pub fn write_safetensors_with<W, M, F, const N_INPUTS: usize, const N_OUTPUTS: usize>(writer: &mut W, model: &M, names: F) -> Result<(), SafetensorsError>
+where
+    W: Write,
+    M: Module<N_INPUTS, N_OUTPUTS>,
+    F: Fn(usize, ParamKind) -> String
+{
+    let params: Vec<DynTensor<f32>> = collect_params(model).into_iter().enumerate()
+        .map(|(index, param)| if kind_of(index) == ParamKind::Weights { param.transpose() } else { param })
+        .collect();
+    let mut entries = Vec::with_capacity(params.len());
+    let mut offset = 0;
+    for (index, param) in params.iter().enumerate() {
+        let kind = kind_of(index);
+        let shape = match kind {
+            ParamKind::Weights => format!("[{}, {}]", param.rows(), param.cols()),
+            ParamKind::Biases => format!("[{}]", param.cols())
+        };
+        let len = size_of_val(param.data());
+        entries.push(format!("{}:{{\"dtype\":\"F32\",\"shape\":{},\"data_offsets\":[{},{}]}}", json_string(&names(index / 2, kind)), shape, offset, offset + len));
+        offset += len;
+    }
+    let mut header = format!("{{{}}}", entries.join(","));
+    // Pad with spaces so the data starts 8 byte aligned
+    header.extend(std::iter::repeat_n(' ', header.len().next_multiple_of(8) - header.len()));
+
+    writer.write_all(&(header.len() as u64).to_le_bytes())?;
+    writer.write_all(header.as_bytes())?;
+    for param in params.iter() {
+        let bytes: Vec<u8> = param.data().iter().flat_map(|el| el.to_le_bytes()).collect();
+        writer.write_all(&bytes)?;
+    }
+    Ok(())
+}
+
+pub fn write_safetensors<W: Write, M: Module<N_INPUTS, N_OUTPUTS>, const N_INPUTS: usize, const N_OUTPUTS: usize>(writer: &mut W, model: &M) -> Result<(), SafetensorsError> {
+    write_safetensors_with(writer, model, default_name)
+}
+
+struct TensorInfo {
+    dtype: String,
+    shape: Vec<usize>,
+    offsets: (usize, usize)
+}
+
+fn tensor_info(name: &str, value: json::Value) -> Result<TensorInfo, SafetensorsError> {
+    let bad = |field: &str| SafetensorsError::BadHeader(format!("{} of {} is missing or invalid", field, name));
+    let mut fields = match value {
+        json::Value::Object(fields) => fields,
+        _ => return Err(bad("entry"))
+    };
+    let mut take = |field: &str| fields.iter().position(|(key, _)| key == field).map(|i| fields.swap_remove(i).1).ok_or_else(|| bad(field));
+    let dtype = match take("dtype")? {
+        json::Value::String(dtype) => dtype,
+        _ => return Err(bad("dtype"))
+    };
+    let shape = take("shape")?.as_usizes().ok_or_else(|| bad("shape"))?;
+    let offsets = match take("data_offsets")?.as_usizes().as_deref() {
+        Some(&[begin, end]) if begin <= end => (begin, end),
+        _ => return Err(bad("data_offsets"))
+    };
+    Ok(TensorInfo { dtype, shape, offsets })
+}
+
+// Restores every dense layer's parameters from the tensors named by `names(layer index, kind)`.
+// Other tensors in the file are ignored. The model is only changed if all parameters fit
+pub fn read_safetensors_with<R, M, F, const N_INPUTS: usize, const N_OUTPUTS: usize>(reader: &mut R, model: &mut M, names: F) -> Result<(), SafetensorsError>
+where
+    R: Read,
+    M: Module<N_INPUTS, N_OUTPUTS>,
+    F: Fn(usize, ParamKind) -> String
+{
+    let mut len = [0u8; 8];
+    reader.read_exact(&mut len)?;
+    let len = u64::from_le_bytes(len);
+    if len > MAX_HEADER_LEN {
+        return Err(SafetensorsError::BadHeader(format!("header length {} is too large", len)))
+    }
+    let mut header = Vec::new();
+    reader.by_ref().take(len).read_to_end(&mut header)?;
+    if header.len() as u64 != len {
+        return Err(SafetensorsError::Truncated)
+    }
+    let header = String::from_utf8(header).map_err(|_| SafetensorsError::BadHeader("not valid UTF-8".to_string()))?;
+    let entries = match json::parse(&header).map_err(SafetensorsError::BadHeader)? {
+        json::Value::Object(entries) => entries,
+        _ => return Err(SafetensorsError::BadHeader("not a JSON object".to_string()))
+    };
+    let mut tensors = HashMap::new();
+    for (name, value) in entries {
+        if name != "__metadata__" {
+            let info = tensor_info(&name, value)?;
+            tensors.insert(name, info);
+        }
+    }
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+
+    let mut params = Vec::new();
+    for (index, (rows, cols)) in param_shapes(model).into_iter().enumerate() {
+        let kind = kind_of(index);
+        let name = names(index / 2, kind);
+        let info = tensors.get(&name).ok_or_else(|| SafetensorsError::MissingTensor(name.clone()))?;
+        if info.dtype != "F32" {
+            return Err(SafetensorsError::UnsupportedDtype { name, dtype: info.dtype.clone() })
+        }
+        let fits = match kind {
+            ParamKind::Weights => info.shape == [cols, rows],
+            ParamKind::Biases => info.shape == [cols] || info.shape == [1, cols]
+        };
+        if !fits {
+            let expected = if kind == ParamKind::Weights { vec![cols, rows] } else { vec![cols] };
+            return Err(SafetensorsError::ShapeMismatch { name, expected, found: info.shape.clone() })
+        }
+        let (begin, end) = info.offsets;
+        if end - begin != rows * cols * size_of::<f32>() || end > data.len() {
+            return Err(SafetensorsError::BadOffsets(name))
+        }
+        let values = data[begin..end].chunks_exact(4).map(|el| f32::from_le_bytes([el[0], el[1], el[2], el[3]])).collect();
+        let param = match kind {
+            ParamKind::Weights => DynTensor::from_vec(cols, rows, values).expect("length was checked against the shape").transpose(),
+            ParamKind::Biases => DynTensor::from_vec(rows, cols, values).expect("length was checked against the shape")
+        };
+        params.push(param);
+    }
+    restore_params(model, params).expect("shapes were checked against the model");
+    Ok(())
+}
+
+pub fn read_safetensors<R: Read, M: Module<N_INPUTS, N_OUTPUTS>, const N_INPUTS: usize, const N_OUTPUTS: usize>(reader: &mut R, model: &mut M) -> Result<(), SafetensorsError> {
+    read_safetensors_with(reader, model, default_name)
+}
+
+pub fn save_safetensors<M: Module<N_INPUTS, N_OUTPUTS>, const N_INPUTS: usize, const N_OUTPUTS: usize>(path: impl AsRef<Path>, model: &M) -> Result<(), SafetensorsError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_safetensors(&mut writer, model)?;
+    writer.flush()?;
+    Ok(())
+}
+
+pub fn load_safetensors<M: Module<N_INPUTS, N_OUTPUTS>, const N_INPUTS: usize, const N_OUTPUTS: usize>(path: impl AsRef<Path>, model: &mut M) -> Result<(), SafetensorsError> {
+    read_safetensors(&mut BufReader::new(File::open(path)?), model)
+}
+
+// Just enough of a JSON parser for safetensors headers
+mod json {
+    pub enum Value {
+        // true, false and null, which safetensors headers don't use
+        Literal,
+        Number(f64),
+        String(String),
+        Array(Vec<Value>),
+        // Keys in file order
+        Object(Vec<(String, Value)>)
+    }
+
+    impl Value {
+        // Array of non-negative integers, e.g. a shape
+        pub fn as_usizes(&self) -> Option<Vec<usize>> {
+            match self {
+                Value::Array(values) => values.iter().map(|value| match value {
+                    Value::Number(n) if *n >= 0.0 && n.fract() == 0.0 => Some(*n as usize),
+                    _ => None
+                }).collect(),
+                _ => None
+            }
+        }
+    }
+
+    // Headers only nest 3 deep, the limit keeps a hostile header from overflowing the stack
+    const MAX_DEPTH: usize = 64;
+
+    struct Parser<'a> {
+        bytes: &'a [u8],
+        pos: usize,
+        // Number of arrays and objects the parser is currently inside
+        depth: usize
+    }
+
+    pub fn parse(text: &str) -> Result<Value, String> {
+        let mut parser = Parser { bytes: text.as_bytes(), pos: 0, depth: 0 };
+        let value = parser.value()?;
+        parser.skip_ws();
+        if parser.pos != parser.bytes.len() {
+            return Err(format!("unexpected data at byte {}", parser.pos))
+        }
+        Ok(value)
+    }
+
+    impl Parser<'_> {
+        fn skip_ws(&mut self) {
+            while self.pos < self.bytes.len() && self.bytes[self.pos].is_ascii_whitespace() {
+                self.pos += 1;
+            }
+        }
+
+        fn peek(&mut self) -> Option<u8> {
+            self.skip_ws();
+            self.bytes.get(self.pos).copied()
+        }
+
+        fn expect(&mut self, byte: u8) -> Result<(), String> {
+            if self.peek() != Some(byte) {
+                return Err(format!("expected '{}' at byte {}", byte as char, self.pos))
+            }
+            self.pos += 1;
+            Ok(())
+        }
+
+        fn literal(&mut self, word: &str) -> Result<Value, String> {
+            if !self.bytes[self.pos..].starts_with(word.as_bytes()) {
+                return Err(format!("unexpected token at byte {}", self.pos))
+            }
+            self.pos += word.len();
+            Ok(Value::Literal)
+        }
+
+        fn value(&mut self) -> Result<Value, String> {
+            match self.peek() {
+                Some(b'{' | b'[') if self.depth == MAX_DEPTH => Err(format!("nesting deeper than {} at byte {}", MAX_DEPTH, self.pos)),
+                Some(b'{') => self.nested(Self::object),
+                Some(b'[') => self.nested(Self::array),
+                Some(b'"') => Ok(Value::String(self.string()?)),
+                Some(b't') => self.literal("true"),
+                Some(b'f') => self.literal("false"),
+                Some(b'n') => self.literal("null"),
+                Some(b'-' | b'0'..=b'9') => self.number(),
+                _ => Err(format!("unexpected token at byte {}", self.pos))
+            }
+        }
+
+        fn nested(&mut self, parse: fn(&mut Self) -> Result<Value, String>) -> Result<Value, String> {
+            self.depth += 1;
+            let value = parse(self);
+            self.depth -= 1;
+            value
+        }
+
+        fn object(&mut self) -> Result<Value, String> {
+            self.expect(b'{')?;
+            let mut fields = Vec::new();
+            if self.peek() == Some(b'}') {
+                self.pos += 1;
+                return Ok(Value::Object(fields))
+            }
+            loop {
+                self.skip_ws();
+                let key = self.string()?;
+                self.expect(b':')?;
+                fields.push((key, self.value()?));
+                match self.peek() {
+                    Some(b',') => self.pos += 1,
+                    _ => break
+                }
+            }
+            self.expect(b'}')?;
+            Ok(Value::Object(fields))
+        }
+
+        fn array(&mut self) -> Result<Value, String> {
+            self.expect(b'[')?;
+            let mut values = Vec::new();
+            if self.peek() == Some(b']') {
+                self.pos += 1;
+                return Ok(Value::Array(values))
+            }
+            loop {
+                values.push(self.value()?);
+                match self.peek() {
+                    Some(b',') => self.pos += 1,
+                    _ => break
+                }
+            }
+            self.expect(b']')?;
+            Ok(Value::Array(values))
+        }
+
+        fn number(&mut self) -> Result<Value, String> {
+            let start = self.pos;
+            while self.pos < self.bytes.len() && matches!(self.bytes[self.pos], b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9') {
+                self.pos += 1;
+            }
+            let text = std::str::from_utf8(&self.bytes[start..self.pos]).expect("digits are ASCII");
+            text.parse().map(Value::Number).map_err(|_| format!("bad number {:?} at byte {}", text, start))
+        }
+
+        fn string(&mut self) -> Result<String, String> {
+            self.expect(b'"')?;
+            let mut res = Vec::new();
+            loop {
+                let byte = *self.bytes.get(self.pos).ok_or("unterminated string")?;
+                self.pos += 1;
+                match byte {
+                    b'"' => break,
+                    b'\\' => {
+                        let escape = *self.bytes.get(self.pos).ok_or("unterminated string")?;
+                        self.pos += 1;
+                        let c = match escape {
+                            b'"' => '"',
+                            b'\\' => '\\',
+                            b'/' => '/',
+                            b'b' => '\u{8}',
+                            b'f' => '\u{c}',
+                            b'n' => '\n',
+                            b'r' => '\r',
+                            b't' => '\t',
+                            b'u' => {
+                                let hex = self.bytes.get(self.pos..self.pos + 4).ok_or("unterminated escape")?;
+                                self.pos += 4;
+                                let code = std::str::from_utf8(hex).ok().and_then(|hex| u32::from_str_radix(hex, 16).ok()).ok_or("bad unicode escape")?;
+                                char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER)
+                            }
+                            _ => return Err(format!("bad escape at byte {}", self.pos - 1))
+                        };
+                        let mut buf = [0u8; 4];
+                        res.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                    }
+                    byte => res.push(byte)
+                }
+            }
+            String::from_utf8(res).map_err(|_| "string is not valid UTF-8".to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activator::relu::ReLU;
+    use crate::layer::{DenseLayer, Layer};
+    use crate::model::Sequential;
+
+    fn model() -> Sequential<Sequential<DenseLayer<2, 4>, ReLU<4>, 4>, DenseLayer<4, 3>, 4> {
+        Sequential::new(DenseLayer::<2, 4>::new(), ReLU::new()).then(DenseLayer::<4, 3>::new())
+    }
+
+    #[test]
+    fn round_trip() {
+        let source = model();
+        let mut bytes = Vec::new();
+        write_safetensors(&mut bytes, &source).unwrap();
+        let header_len = u64::from_le_bytes(bytes[..8].try_into().unwrap()) as usize;
+        assert_eq!(header_len % 8, 0);
+        let header = std::str::from_utf8(&bytes[8..8 + header_len]).unwrap();
+        assert!(header.contains("\"layers.0.weight\":{\"dtype\":\"F32\",\"shape\":[4, 2],\"data_offsets\":[0,32]}"));
+        assert!(header.contains("\"layers.1.bias\":{\"dtype\":\"F32\",\"shape\":[3]"));
+
+        let mut restored = model();
+        read_safetensors(&mut bytes.as_slice(), &mut restored).unwrap();
+        assert_eq!(restored.first().first().weights(), source.first().first().weights());
+        assert_eq!(restored.second().biases(), source.second().biases());
+    }
+
+    #[test]
+    fn custom_names_and_errors() {
+        let names = |layer: usize, kind: ParamKind| format!("fc{}.{}", layer + 1, if kind == ParamKind::Weights { "w" } else { "b" });
+        let mut bytes = Vec::new();
+        write_safetensors_with(&mut bytes, &model(), names).unwrap();
+        read_safetensors_with(&mut bytes.as_slice(), &mut model(), names).unwrap();
+
+        let err = read_safetensors(&mut bytes.as_slice(), &mut model()).unwrap_err();
+        assert_eq!(err.to_string(), "safetensors: no tensor named layers.0.weight");
+        let mut wider = Sequential::new(DenseLayer::<2, 5>::new(), ReLU::new()).then(DenseLayer::<5, 3>::new());
+        let err = read_safetensors_with(&mut bytes.as_slice(), &mut wider, names).unwrap_err();
+        assert!(matches!(err, SafetensorsError::ShapeMismatch { .. }));
+        assert!(matches!(read_safetensors(&mut &bytes[..4], &mut model()), Err(SafetensorsError::Truncated)));
+    }
+
+    #[test]
+    fn reads_other_writers() {
+        // Header with metadata, escapes, another dtype and extra whitespace, as other libraries write it
+        let header = r#"{"__metadata__": {"format": "pt"}, "other\"name": {"dtype": "I64", "shape": [], "data_offsets": [0, 8]}, "layers.0.weight": {"dtype": "F32", "shape": [2, 1], "data_offsets": [8, 16]}, "layers.0.bias": {"dtype": "F32", "shape": [2], "data_offsets": [16, 24]}}"#;
+        let mut bytes = (header.len() as u64).to_le_bytes().to_vec();
+        bytes.extend(header.as_bytes());
+        bytes.extend([0u8; 8]);
+        for el in [1.0f32, 2.0, 0.5, -0.5] {
+            bytes.extend(el.to_le_bytes());
+        }
+        let mut layer = DenseLayer::<1, 2>::new();
+        read_safetensors(&mut bytes.as_slice(), &mut layer).unwrap();
+        assert_eq!(layer.weights(), &crate::tensor::Tensor::from_data([[1.0, 2.0]]));
+        assert_eq!(layer.biases(), &crate::tensor::Tensor::from_data([[0.5, -0.5]]));
+    }
+
+    #[test]
+    fn reads_torch_weight_layout() {
+        // torch.nn.Linear(2, 2) with weight [[1, 2], [3, 4]], one row per output neuron
+        let header = r#"{"layers.0.weight":{"dtype":"F32","shape":[2,2],"data_offsets":[0,16]},"layers.0.bias":{"dtype":"F32","shape":[2],"data_offsets":[16,24]}}"#;
+        let mut bytes = (header.len() as u64).to_le_bytes().to_vec();
+        bytes.extend(header.as_bytes());
+        for el in [1.0f32, 2.0, 3.0, 4.0, 0.0, 0.0] {
+            bytes.extend(el.to_le_bytes());
+        }
+        let mut layer = DenseLayer::<2, 2>::new();
+        read_safetensors(&mut bytes.as_slice(), &mut layer).unwrap();
+        assert_eq!(layer.weights(), &crate::tensor::Tensor::from_data([[1.0, 3.0], [2.0, 4.0]]));
+
+        let mut written = Vec::new();
+        write_safetensors(&mut written, &layer).unwrap();
+        assert_eq!(&written[written.len() - 24..], &bytes[bytes.len() - 24..]);
+    }
+
+    #[test]
+    fn rejects_deeply_nested_headers() {
+        let header = "[".repeat(200_000);
+        let mut bytes = (header.len() as u64).to_le_bytes().to_vec();
+        bytes.extend(header.as_bytes());
+        let err = read_safetensors(&mut bytes.as_slice(), &mut model()).unwrap_err();
+        assert!(matches!(err, SafetensorsError::BadHeader(_)), "{}", err);
+
+        let nested = format!("{}{}", "[".repeat(64), "]".repeat(64));
+        assert!(json::parse(&nested).is_ok());
+        assert!(json::parse(&format!("[{}]", nested)).is_err());
+    }
+}
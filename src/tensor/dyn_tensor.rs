@@ -0,0 +1,307 @@
+use std::{fmt, iter::Sum, ops};
+
+use num_traits::FromPrimitive;
+
+use super::Tensor;
+
+// Matrix whose shape is only known at runtime, e.g. a dataset loaded from disk.
+// Data is stored row-major in a single Vec
+#[derive(Debug, Clone, PartialEq)]
+pub struct DynTensor<T> {
+    rows: usize,
+    cols: usize,
+    data: Vec<T>
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShapeError {
+    // Operation that failed and the shapes it was given
+    Incompatible { op: &'static str, lhs: Vec<usize>, rhs: Vec<usize> },
+    // Axis that doesn't exist in a tensor of rank `rank`
+    InvalidAxis { op: &'static str, axis: usize, rank: usize },
+    // Axes that are not each of 0..rank exactly once
+    InvalidPermutation { axes: Vec<usize> }
+}
+
+impl ShapeError {
+    pub fn new(op: &'static str, lhs: &[usize], rhs: &[usize]) -> Self {
+        ShapeError::Incompatible { op, lhs: lhs.to_vec(), rhs: rhs.to_vec() }
+    }
+}
+
+fn fmt_shape(shape: &[usize]) -> String {
+    shape.iter().map(|dim| dim.to_string()).collect::<Vec<_>>().join("x")
+}
+
+impl fmt::Display for ShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShapeError::Incompatible { op, lhs, rhs } => write!(f, "{}: incompatible shapes {} and {}", op, fmt_shape(lhs), fmt_shape(rhs)),
+            ShapeError::InvalidAxis { op, axis, rank } => write!(f, "{}: no axis {} in a tensor of rank {}", op, axis, rank),
+            ShapeError::InvalidPermutation { axes } => write!(f, "permute: {:?} is not a permutation of the axes 0..{}", axes, axes.len())
+        }
+    }
+}
+
+impl std::error::Error for ShapeError {}
+
+impl<T> DynTensor<T> {
+    pub fn new(rows: usize, cols: usize) -> Self
+    where
+        T: Default + Clone,
+    {
+        DynTensor::fill(rows, cols, T::default())
+    }
+
+    pub fn fill(rows: usize, cols: usize, val: T) -> Self
+    where
+        T: Clone,
+    {
+        DynTensor { rows, cols, data: vec![val; rows * cols] }
+    }
+
+    // `data` holds the elements row after row and must have exactly rows * cols elements
+    pub fn from_vec(rows: usize, cols: usize, data: Vec<T>) -> Result<Self, ShapeError> {
+        if data.len() != rows * cols {
+            return Err(ShapeError::new("from_vec", &[rows, cols], &[data.len()]))
+        }
+        Ok(DynTensor { rows, cols, data })
+    }
+
+    pub fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn data(&self) -> &[T] {
+        &self.data
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.data
+    }
+
+    pub fn row(&self, i: usize) -> &[T] {
+        &self.data[i * self.cols..(i + 1) * self.cols]
+    }
+
+    pub fn reshape(self, rows: usize, cols: usize) -> Result<Self, ShapeError> {
+        if rows * cols != self.data.len() {
+            return Err(ShapeError::new("reshape", &[self.rows, self.cols], &[rows, cols]))
+        }
+        Ok(DynTensor { rows, cols, data: self.data })
+    }
+
+    pub fn transpose(&self) -> DynTensor<T>
+    where
+        T: Copy,
+    {
+        let mut data = Vec::with_capacity(self.data.len());
+        for j in 0..self.cols {
+            for i in 0..self.rows {
+                data.push(self[(i, j)]);
+            }
+        }
+        DynTensor { rows: self.cols, cols: self.rows, data }
+    }
+
+    pub fn apply<F>(&mut self, func: F)
+    where
+        F: Fn(&T) -> T
+    {
+        for el in self.data.iter_mut() {
+            *el = func(el);
+        }
+    }
+
+    // Element-wise combination of two tensors of the same shape
+    fn zip_with<F>(&self, rhs: &DynTensor<T>, op: &'static str, func: F) -> Result<DynTensor<T>, ShapeError>
+    where
+        T: Copy,
+        F: Fn(T, T) -> T
+    {
+        if self.shape() != rhs.shape() {
+            return Err(ShapeError::new(op, &[self.rows, self.cols], &[rhs.rows, rhs.cols]))
+        }
+        let data = self.data.iter().zip(rhs.data.iter()).map(|(a, b)| func(*a, *b)).collect();
+        Ok(DynTensor { rows: self.rows, cols: self.cols, data })
+    }
+
+    pub fn try_add(&self, rhs: &DynTensor<T>) -> Result<DynTensor<T>, ShapeError>
+    where
+        T: Copy + ops::Add<Output = T>,
+    {
+        self.zip_with(rhs, "add", |a, b| a + b)
+    }
+
+    pub fn try_sub(&self, rhs: &DynTensor<T>) -> Result<DynTensor<T>, ShapeError>
+    where
+        T: Copy + ops::Sub<Output = T>,
+    {
+        self.zip_with(rhs, "sub", |a, b| a - b)
+    }
+
+    // Element-wise (Hadamard) product, use `matmul` for the matrix product
+    pub fn try_mul(&self, rhs: &DynTensor<T>) -> Result<DynTensor<T>, ShapeError>
+    where
+        T: Copy + ops::Mul<Output = T>,
+    {
+        self.zip_with(rhs, "mul", |a, b| a * b)
+    }
+
+    pub fn try_div(&self, rhs: &DynTensor<T>) -> Result<DynTensor<T>, ShapeError>
+    where
+        T: Copy + ops::Div<Output = T>,
+    {
+        self.zip_with(rhs, "div", |a, b| a / b)
+    }
+
+    pub fn matmul(&self, rhs: &DynTensor<T>) -> Result<DynTensor<T>, ShapeError>
+    where
+        T: Copy + Default + ops::Add<Output = T> + ops::Mul<Output = T>,
+    {
+        if self.cols != rhs.rows {
+            return Err(ShapeError::new("matmul", &[self.rows, self.cols], &[rhs.rows, rhs.cols]))
+        }
+        let mut res = DynTensor::new(self.rows, rhs.cols);
+        for i in 0..self.rows {
+            for j in 0..rhs.cols {
+                let mut sum = T::default();
+                for k in 0..self.cols {
+                    sum = sum + self[(i, k)] * rhs[(k, j)];
+                }
+                res[(i, j)] = sum;
+            }
+        }
+        Ok(res)
+    }
+
+    // Same broadcasting rules as `Tensor::broadcast`: every dimension must match or be 1
+    pub fn broadcast(&self, rows: usize, cols: usize) -> Result<DynTensor<T>, ShapeError>
+    where
+        T: Copy,
+    {
+        if (self.rows != rows && self.rows != 1) || (self.cols != cols && self.cols != 1) {
+            return Err(ShapeError::new("broadcast", &[self.rows, self.cols], &[rows, cols]))
+        }
+        let mut data = Vec::with_capacity(rows * cols);
+        for i in 0..rows {
+            for j in 0..cols {
+                let src_i = if self.rows == 1 { 0 } else { i };
+                let src_j = if self.cols == 1 { 0 } else { j };
+                data.push(self[(src_i, src_j)]);
+            }
+        }
+        Ok(DynTensor { rows, cols, data })
+    }
+
+    pub fn sum(&self) -> T
+    where
+        T: Sum + Copy,
+    {
+        self.data.iter().copied().sum()
+    }
+
+    pub fn mean(&self) -> T
+    where
+        T: Copy + Sum + ops::Div<Output = T> + FromPrimitive,
+    {
+        let count = T::from_usize(self.data.len()).expect("Failed to convert usize to T");
+        self.sum() / count
+    }
+}
+
+impl<T> ops::Index<(usize, usize)> for DynTensor<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        assert!(row < self.rows && col < self.cols, "Index ({}, {}) out of bounds for shape {}x{}", row, col, self.rows, self.cols);
+        &self.data[row * self.cols + col]
+    }
+}
+
+impl<T> ops::IndexMut<(usize, usize)> for DynTensor<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        assert!(row < self.rows && col < self.cols, "Index ({}, {}) out of bounds for shape {}x{}", row, col, self.rows, self.cols);
+        &mut self.data[row * self.cols + col]
+    }
+}
+
+impl<T, const ROWS: usize, const COLS: usize> From<Tensor<T, ROWS, COLS>> for DynTensor<T>
+where
+    T: Copy,
+{
+    fn from(tensor: Tensor<T, ROWS, COLS>) -> Self {
+        DynTensor::from(&tensor)
+    }
+}
+
+impl<T, const ROWS: usize, const COLS: usize> From<&Tensor<T, ROWS, COLS>> for DynTensor<T>
+where
+    T: Copy,
+{
+    fn from(tensor: &Tensor<T, ROWS, COLS>) -> Self {
+        DynTensor { rows: ROWS, cols: COLS, data: tensor.data.iter().flatten().copied().collect() }
+    }
+}
+
+impl<T, const ROWS: usize, const COLS: usize> TryFrom<DynTensor<T>> for Tensor<T, ROWS, COLS>
+where
+    T: Default + Copy,
+{
+    type Error = ShapeError;
+
+    fn try_from(tensor: DynTensor<T>) -> Result<Self, ShapeError> {
+        Tensor::try_from(&tensor)
+    }
+}
+
+impl<T, const ROWS: usize, const COLS: usize> TryFrom<&DynTensor<T>> for Tensor<T, ROWS, COLS>
+where
+    T: Default + Copy,
+{
+    type Error = ShapeError;
+
+    fn try_from(tensor: &DynTensor<T>) -> Result<Self, ShapeError> {
+        if tensor.shape() != (ROWS, COLS) {
+            return Err(ShapeError::new("convert", &[tensor.rows, tensor.cols], &[ROWS, COLS]))
+        }
+        let mut res: Tensor<T, ROWS, COLS> = Tensor::new();
+        for (dst, src) in res.data.iter_mut().zip(tensor.data.chunks_exact(COLS.max(1))) {
+            dst.copy_from_slice(src);
+        }
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shape_checked_ops() {
+        let a = DynTensor::from_vec(2, 3, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).unwrap();
+        let b = DynTensor::from_vec(3, 2, vec![1.0, 0.0, 0.0, 1.0, 1.0, 1.0]).unwrap();
+        assert!(a.try_add(&b).is_err());
+        assert_eq!(a.matmul(&b).unwrap(), DynTensor::from_vec(2, 2, vec![4.0, 5.0, 10.0, 11.0]).unwrap());
+        assert!(b.matmul(&b).is_err());
+        assert!(DynTensor::from_vec(2, 2, vec![1.0; 3]).is_err());
+    }
+
+    #[test]
+    fn tensor_round_trip() {
+        let tensor: Tensor<i32, 2, 3> = Tensor::from_data([[1, 2, 3], [4, 5, 6]]);
+        let dynamic = DynTensor::from(tensor.clone());
+        assert_eq!(dynamic.shape(), (2, 3));
+        assert_eq!(dynamic[(1, 0)], 4);
+        assert_eq!(Tensor::<i32, 2, 3>::try_from(&dynamic).unwrap(), tensor);
+        assert!(Tensor::<i32, 3, 2>::try_from(dynamic).is_err());
+    }
+}
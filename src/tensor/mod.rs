@@ -1,12 +1,28 @@
-use num_traits::{cast::FromPrimitive};
+use num_traits::{cast::FromPrimitive, Float};
 use std::{fmt::Display, iter::{zip, Sum}, ops};
 use rand;
 
+pub mod dyn_tensor;
+pub mod nd_tensor;
+pub use dyn_tensor::{DynTensor, ShapeError};
+pub use nd_tensor::{NdTensor, Tensor3, Tensor4};
 
+
+// Data lives on the heap so large tensors can't overflow the stack, and Tensor is not Copy
+// so copying the whole buffer always takes an explicit clone()
 #[derive(Debug)]
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub struct Tensor<T, const ROWS: usize, const COLS: usize> {
-    data: [[T; COLS]; ROWS]
+    data: Box<[[T; COLS]; ROWS]>
+}
+
+// Allocates the rows directly on the heap, only a single row is ever built on the stack
+fn boxed_fill<T: Copy, const ROWS: usize, const COLS: usize>(val: T) -> Box<[[T; COLS]; ROWS]> {
+    let rows: Box<[[T; COLS]]> = vec![[val; COLS]; ROWS].into_boxed_slice();
+    match rows.try_into() {
+        Ok(data) => data,
+        Err(_) => unreachable!("vec was allocated with ROWS rows")
+    }
 }
 
 pub enum Axis {
@@ -51,16 +67,18 @@ impl<T, const ROWS: usize, const COLS: usize> Tensor<T, ROWS, COLS> {
         T: Default + Copy,
     {
         Tensor {
-            data: [[T::default(); COLS]; ROWS]
+            data: boxed_fill(T::default())
         }
     }
 
+    // The array is built on the stack before it is moved to the heap, meant for small literals.
+    // Build large tensors with new, fill or sample_with and fill them through indexing
     pub fn from_data(data: [[T; COLS]; ROWS]) -> Self
     where
         T: Default + Copy,
     {
         Tensor {
-            data: data
+            data: Box::new(data)
         }
     }
 
@@ -69,21 +87,53 @@ impl<T, const ROWS: usize, const COLS: usize> Tensor<T, ROWS, COLS> {
         T: Copy,
     {
         Tensor {
-            data: [[val; COLS]; ROWS]
+            data: boxed_fill(val)
         }
     }
 
     pub fn rand_fill() -> Self 
     where
-        T: Copy,
+        T: Default + Copy,
         rand::distr::StandardUniform: rand::distr::Distribution<T>,
     {
-        Tensor {
-            data: [[rand::random::<T>(); COLS]; ROWS]
+        Tensor::rand_fill_with(&mut rand::rng())
+    }
+
+    // Every element is drawn independently from `rng`, pass a seeded rng (e.g. StdRng::seed_from_u64) for reproducible runs
+    pub fn rand_fill_with<R>(rng: &mut R) -> Self 
+    where
+        T: Default + Copy,
+        R: rand::Rng + ?Sized,
+        rand::distr::StandardUniform: rand::distr::Distribution<T>,
+    {
+        Tensor::sample_with(rng, rand::distr::StandardUniform)
+    }
+
+    // Every element is drawn independently from `distr`
+    pub fn sample_with<R, D>(rng: &mut R, distr: D) -> Self 
+    where
+        T: Default + Copy,
+        R: rand::Rng + ?Sized,
+        D: rand::distr::Distribution<T>,
+    {
+        let mut res: Tensor<T, ROWS, COLS> = Tensor::new();
+        for i in 0..ROWS {
+            for j in 0..COLS {
+                res.data[i][j] = distr.sample(rng);
+            }
         }
+        res
+    }
+
+    pub fn rand_fill_seeded(seed: u64) -> Self 
+    where
+        T: Default + Copy,
+        rand::distr::StandardUniform: rand::distr::Distribution<T>,
+    {
+        Tensor::rand_fill_with(&mut <rand::rngs::StdRng as rand::SeedableRng>::seed_from_u64(seed))
     }
 
-    pub fn dot(self, rhs: Tensor<T, COLS, 1>) -> T
+    pub fn dot(&self, rhs: &Tensor<T, COLS, 1>) -> T
     where 
         T: Default + ops::Mul<Output = T> + ops::Add<Output = T> + Copy
     {
@@ -94,7 +144,7 @@ impl<T, const ROWS: usize, const COLS: usize> Tensor<T, ROWS, COLS> {
         res
     }
 
-    pub fn transpose(self) -> Tensor<T, COLS, ROWS>
+    pub fn transpose(&self) -> Tensor<T, COLS, ROWS>
     where 
         T: Default + Copy
     {
@@ -124,7 +174,7 @@ impl<T, const ROWS: usize, const COLS: usize> Tensor<T, ROWS, COLS> {
         );
 
         let mut result = Tensor {
-            data: [[self.data[0][0]; T_COLS]; T_ROWS],
+            data: boxed_fill(self.data[0][0]),
         };
 
         for i in 0..T_ROWS {
@@ -133,7 +183,7 @@ impl<T, const ROWS: usize, const COLS: usize> Tensor<T, ROWS, COLS> {
                 let src_i = if ROWS == 1 { 0 } else { i };
                 let src_j = if COLS == 1 { 0 } else { j };
                 
-                result.data[i][j] = self.data[src_i][src_j].clone();
+                result.data[i][j] = self.data[src_i][src_j];
             }
         }
 
@@ -162,9 +212,11 @@ impl<T, const ROWS: usize, const COLS: usize> Tensor<T, ROWS, COLS> {
         }
     }
 
-    pub fn argmax(&self, axis: Axis) -> Option<AxisRes<usize, ROWS, COLS>>
+    // Index of the first element in every row (Axis::Row) or column (Axis::Col) that no other element beats
+    fn arg_reduce_axis<F>(&self, axis: Axis, beats: F) -> Option<AxisRes<usize, ROWS, COLS>>
     where 
-        T: PartialOrd + Default + Copy,
+        T: Copy,
+        F: Fn(&T, &T) -> bool
     {
         if COLS == 0 || ROWS == 0 {
             return None
@@ -173,37 +225,50 @@ impl<T, const ROWS: usize, const COLS: usize> Tensor<T, ROWS, COLS> {
             Axis::Row => {
                 let mut res: Tensor<usize, ROWS, 1> = Tensor::new();
                 for row_id in 0..ROWS {
-                    let mut max: T = self.data[row_id][0];
-                    let mut max_id: usize = 0;
+                    let mut best: T = self.data[row_id][0];
+                    let mut best_id: usize = 0;
                     for col_id in 0..COLS {
-                        if self.data[row_id][col_id] > max {
-                            max = self.data[row_id][col_id];
-                            max_id = col_id;
+                        if beats(&self.data[row_id][col_id], &best) {
+                            best = self.data[row_id][col_id];
+                            best_id = col_id;
                         }
                     }
-                    res.data[row_id][0] = max_id;
+                    res.data[row_id][0] = best_id;
                 }
                 Some(AxisRes::Row(res))
             }
             Axis::Col => {
                 let mut res: Tensor<usize, 1, COLS> = Tensor::new();
-                let transposed = self.transpose();
                 for col_id in 0..COLS {
-                    let mut max: T = transposed.data[col_id][0];
-                    let mut max_id: usize = 0;
+                    let mut best: T = self.data[0][col_id];
+                    let mut best_id: usize = 0;
                     for row_id in 0..ROWS {
-                        if transposed.data[col_id][row_id] > max {
-                            max = transposed.data[col_id][row_id];
-                            max_id = row_id;
+                        if beats(&self.data[row_id][col_id], &best) {
+                            best = self.data[row_id][col_id];
+                            best_id = row_id;
                         }
                     }
-                    res.data[col_id][0] = max_id;
+                    res.data[0][col_id] = best_id;
                 }
                 Some(AxisRes::Col(res))
             }
         }
     }
 
+    pub fn argmax(&self, axis: Axis) -> Option<AxisRes<usize, ROWS, COLS>>
+    where 
+        T: PartialOrd + Default + Copy,
+    {
+        self.arg_reduce_axis(axis, |el, max| el > max)
+    }
+
+    pub fn argmin(&self, axis: Axis) -> Option<AxisRes<usize, ROWS, COLS>>
+    where 
+        T: PartialOrd + Default + Copy,
+    {
+        self.arg_reduce_axis(axis, |el, min| el < min)
+    }
+
     pub fn eq(&self, rhs: Tensor<T, ROWS, COLS>) -> Tensor<bool, ROWS, COLS>
     where
         T: PartialEq + Default + Copy
@@ -273,37 +338,100 @@ impl<T, const ROWS: usize, const COLS: usize> Tensor<T, ROWS, COLS> {
         max_res
     }
 
-    pub fn max_axis(&self, axis: Axis) -> AxisRes<T, ROWS, COLS> 
-    where 
-        T: Default + Copy + std::ops::Add<Output = T>
+    // Folds every row (Axis::Row) or column (Axis::Col) into one value, starting from its first element.
+    // An empty row or column (COLS or ROWS of 0) reduces to T::default()
+    fn reduce_axis<F>(&self, axis: Axis, f: F) -> AxisRes<T, ROWS, COLS>
+    where
+        T: Default + Copy,
+        F: Fn(T, T) -> T
     {
         match axis {
             Axis::Row => {
-                // Sum each row (result will be column vector)
                 let mut result = Tensor::<T, ROWS, 1>::new();
                 for i in 0..ROWS {
-                    let mut sum = T::default();
-                    for j in 0..COLS {
-                        sum = sum + self.data[i][j];
-                    }
-                    result.data[i][0] = sum;
+                    result.data[i][0] = self.data[i].iter().copied().reduce(&f).unwrap_or_default();
                 }
                 AxisRes::Row(result)
             }
             Axis::Col => {
-                // Sum each column (result will be row vector)
                 let mut result = Tensor::<T, 1, COLS>::new();
                 for j in 0..COLS {
-                    let mut sum = T::default();
-                    for i in 0..ROWS {
-                        sum = sum + self.data[i][j];
-                    }
-                    result.data[0][j] = sum;
+                    result.data[0][j] = self.data.iter().map(|row| row[j]).reduce(&f).unwrap_or_default();
                 }
                 AxisRes::Col(result)
             }
         }
     }
+
+    pub fn max_axis(&self, axis: Axis) -> AxisRes<T, ROWS, COLS> 
+    where 
+        T: Default + Copy + PartialOrd
+    {
+        self.reduce_axis(axis, |acc, el| if el > acc { el } else { acc })
+    }
+
+    pub fn min_axis(&self, axis: Axis) -> AxisRes<T, ROWS, COLS> 
+    where 
+        T: Default + Copy + PartialOrd
+    {
+        self.reduce_axis(axis, |acc, el| if el < acc { el } else { acc })
+    }
+
+    pub fn prod_axis(&self, axis: Axis) -> AxisRes<T, ROWS, COLS> 
+    where 
+        T: Default + Copy + ops::Mul<Output = T>
+    {
+        self.reduce_axis(axis, |acc, el| acc * el)
+    }
+
+    pub fn mean_axis(&self, axis: Axis) -> AxisRes<T, ROWS, COLS> 
+    where 
+        T: Default + Copy + ops::Add<Output = T> + ops::Div<Output = T> + FromPrimitive
+    {
+        match self.sum_axis(axis) {
+            // The sums of empty rows and columns are T::default(), which is also their mean
+            AxisRes::Row(sum) if COLS == 0 => AxisRes::Row(sum),
+            AxisRes::Col(sum) if ROWS == 0 => AxisRes::Col(sum),
+            AxisRes::Row(sum) => AxisRes::Row(sum / T::from_usize(COLS).expect("Failed to convert usize to T")),
+            AxisRes::Col(sum) => AxisRes::Col(sum / T::from_usize(ROWS).expect("Failed to convert usize to T"))
+        }
+    }
+
+    // Population variance (mean of the squared deviations from the mean)
+    pub fn var_axis(&self, axis: Axis) -> AxisRes<T, ROWS, COLS> 
+    where 
+        T: Default + Copy + ops::Add<Output = T> + ops::Sub<Output = T> + ops::Mul<Output = T> + ops::Div<Output = T> + FromPrimitive
+    {
+        match self.mean_axis(axis) {
+            AxisRes::Row(mean) => {
+                let mut deviations = self.clone() - mean.broadcast::<ROWS, COLS>();
+                deviations.apply(|el| *el * *el);
+                deviations.mean_axis(Axis::Row)
+            }
+            AxisRes::Col(mean) => {
+                let mut deviations = self.clone() - mean.broadcast::<ROWS, COLS>();
+                deviations.apply(|el| *el * *el);
+                deviations.mean_axis(Axis::Col)
+            }
+        }
+    }
+
+    pub fn std_axis(&self, axis: Axis) -> AxisRes<T, ROWS, COLS> 
+    where 
+        T: Default + Copy + Float + FromPrimitive
+    {
+        match self.var_axis(axis) {
+            AxisRes::Row(mut var) => {
+                var.apply(|el| el.sqrt());
+                AxisRes::Row(var)
+            }
+            AxisRes::Col(mut var) => {
+                var.apply(|el| el.sqrt());
+                AxisRes::Col(var)
+            }
+        }
+    }
+
     pub fn any<F>(&self, axis: Axis, mut f: F) -> AxisRes<bool, ROWS, COLS> 
     where
         F: FnMut(&T) -> bool,
@@ -374,6 +502,21 @@ impl<T, const ROWS: usize, const COLS: usize> Tensor<T, ROWS, COLS> {
             }
         }
     }
+
+    // Gathers the rows at `indices` in that order, e.g. a shuffled mini-batch
+    pub fn select_rows<const N: usize>(&self, indices: &[usize]) -> Tensor<T, N, COLS>
+    where
+        T: Default + Copy
+    {
+        assert_eq!(indices.len(), N, "Expected {} row indices, got {}", N, indices.len());
+        let mut res: Tensor<T, N, COLS> = Tensor::new();
+        for (dst, &idx) in res.data.iter_mut().zip(indices) {
+            assert!(idx < ROWS, "Row {} out of bounds for {} rows", idx, ROWS);
+            *dst = self.data[idx];
+        }
+        res
+    }
+
     pub fn index_cols(&self, idx: TensorIndex<ROWS, COLS>) -> Result<Tensor<T, ROWS, 1>, String>
     where
         T: Default + Copy + Display
@@ -393,8 +536,8 @@ impl<T, const ROWS: usize, const COLS: usize> Tensor<T, ROWS, COLS> {
                 }
             }
             TensorIndex::Mask(mask) => {
-                for (row_id, (data_row, mask_row)) in zip(self.data, mask.data).enumerate() {
-                    let mask_row_index = mask_row.iter().position(|el| *el == 1).ok_or_else(|| "Invalid mask")?;
+                for (row_id, (data_row, mask_row)) in zip(self.data.iter(), mask.data.iter()).enumerate() {
+                    let mask_row_index = mask_row.iter().position(|el| *el == 1).ok_or("Invalid mask")?;
                     res.data[row_id][0] = data_row[mask_row_index];
                 }
                 Ok(res)
@@ -411,14 +554,25 @@ impl<T, const ROWS: usize, const COLS: usize> Tensor<T, ROWS, COLS> {
     }
 }
 
-impl<T, const ROWS: usize, const COLS: usize> ops::Add for Tensor<T, ROWS, COLS>
+impl<T, const ROWS: usize, const COLS: usize> Default for Tensor<T, ROWS, COLS>
+where
+    T: Default + Copy,
+{
+    fn default() -> Self {
+        Tensor::new()
+    }
+}
+
+// Operators that take a tensor by value write the result into its buffer instead of allocating a new one,
+// so chains like `a - b + c` allocate nothing. Only matrix multiplication needs a new buffer
+impl<T, const ROWS: usize, const COLS: usize> ops::Add<&Tensor<T, ROWS, COLS>> for Tensor<T, ROWS, COLS>
 where
     T: ops::Add<Output = T> + Copy,  // Element type must support addition and be copyable
 {
     type Output = Self;  // Result of addition is another Tensor with same dimensions
 
-    fn add(self, rhs: Self) -> Tensor<T, ROWS, COLS> {
-        let mut result = self;  // Copy self (requires T: Copy)
+    fn add(self, rhs: &Self) -> Tensor<T, ROWS, COLS> {
+        let mut result = self;
         
         // Element-wise addition
         for i in 0..ROWS {
@@ -431,6 +585,17 @@ where
     }
 }
 
+impl<T, const ROWS: usize, const COLS: usize> ops::Add for Tensor<T, ROWS, COLS>
+where
+    T: ops::Add<Output = T> + Copy,
+{
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Tensor<T, ROWS, COLS> {
+        self + &rhs
+    }
+}
+
 impl<T, const ROWS: usize, const COLS: usize> ops::Add<T> for Tensor<T, ROWS, COLS>
 where
     T: ops::Add<Output = T> + Copy,  // Element type must support addition and be copyable
@@ -438,7 +603,7 @@ where
     type Output = Self;  // Result of addition is another Tensor with same dimensions
 
     fn add(self, scalar: T) -> Tensor<T, ROWS, COLS> {
-        let mut result = self;  // Copy self (requires T: Copy)
+        let mut result = self;
         
         // Element-wise addition
         for i in 0..ROWS {
@@ -451,16 +616,16 @@ where
     }
 }
 
-impl<T, const ROWS: usize, const COLS: usize> ops::Sub for Tensor<T, ROWS, COLS>
+impl<T, const ROWS: usize, const COLS: usize> ops::Sub<&Tensor<T, ROWS, COLS>> for Tensor<T, ROWS, COLS>
 where
-    T: ops::Sub<Output = T> + Copy,  // Element type must support addition and be copyable
+    T: ops::Sub<Output = T> + Copy,  // Element type must support subtraction and be copyable
 {
-    type Output = Self;  // Result of addition is another Tensor with same dimensions
+    type Output = Self;  // Result of subtraction is another Tensor with same dimensions
 
-    fn sub(self, rhs: Self) -> Tensor<T, ROWS, COLS> {
-        let mut result = self;  // Copy self (requires T: Copy)
+    fn sub(self, rhs: &Self) -> Tensor<T, ROWS, COLS> {
+        let mut result = self;
         
-        // Element-wise addition
+        // Element-wise subtraction
         for i in 0..ROWS {
             for j in 0..COLS {
                 result.data[i][j] = result.data[i][j] - rhs.data[i][j];
@@ -471,16 +636,27 @@ where
     }
 }
 
+impl<T, const ROWS: usize, const COLS: usize> ops::Sub for Tensor<T, ROWS, COLS>
+where
+    T: ops::Sub<Output = T> + Copy,
+{
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Tensor<T, ROWS, COLS> {
+        self - &rhs
+    }
+}
+
 impl<T, const ROWS: usize, const COLS: usize> ops::Sub<T> for Tensor<T, ROWS, COLS>
 where
-    T: ops::Sub<Output = T> + Copy,  // Element type must support addition and be copyable
+    T: ops::Sub<Output = T> + Copy,  // Element type must support subtraction and be copyable
 {
-    type Output = Self;  // Result of addition is another Tensor with same dimensions
+    type Output = Self;  // Result of subtraction is another Tensor with same dimensions
 
     fn sub(self, scalar: T) -> Tensor<T, ROWS, COLS> {
-        let mut result = self;  // Copy self (requires T: Copy)
+        let mut result = self;
         
-        // Element-wise addition
+        // Element-wise subtraction
         for i in 0..ROWS {
             for j in 0..COLS {
                 result.data[i][j] = result.data[i][j] - scalar;
@@ -491,13 +667,13 @@ where
     }
 }
 
-impl<T, const LH_ROWS: usize, const LH_COLS: usize, const RH_COLS: usize> ops::Mul<Tensor<T, LH_COLS, RH_COLS>> for Tensor<T, LH_ROWS, LH_COLS>
+impl<T, const LH_ROWS: usize, const LH_COLS: usize, const RH_COLS: usize> ops::Mul<&Tensor<T, LH_COLS, RH_COLS>> for &Tensor<T, LH_ROWS, LH_COLS>
 where
-    T: ops::Add<Output = T> + Default + ops::Mul<Output = T> + Copy + std::fmt::Debug,  // Element type must support addition and be copyable
+    T: ops::Add<Output = T> + Default + ops::Mul<Output = T> + Copy,  // Element type must support addition and multiplication and be copyable
 {
-    type Output = Tensor<T, LH_ROWS, RH_COLS>;  // Result of addition is another Tensor with same dimensions
+    type Output = Tensor<T, LH_ROWS, RH_COLS>;  // Matrix product, inner dimensions must match
 
-    fn mul(self, rhs: Tensor<T, LH_COLS, RH_COLS>) -> Tensor<T, LH_ROWS, RH_COLS> {
+    fn mul(self, rhs: &Tensor<T, LH_COLS, RH_COLS>) -> Tensor<T, LH_ROWS, RH_COLS> {
 
         let mut result = Tensor::<T, LH_ROWS, RH_COLS>::new();
         
@@ -515,11 +691,22 @@ where
     }
 }
 
+impl<T, const LH_ROWS: usize, const LH_COLS: usize, const RH_COLS: usize> ops::Mul<Tensor<T, LH_COLS, RH_COLS>> for Tensor<T, LH_ROWS, LH_COLS>
+where
+    T: ops::Add<Output = T> + Default + ops::Mul<Output = T> + Copy,
+{
+    type Output = Tensor<T, LH_ROWS, RH_COLS>;
+
+    fn mul(self, rhs: Tensor<T, LH_COLS, RH_COLS>) -> Tensor<T, LH_ROWS, RH_COLS> {
+        &self * &rhs
+    }
+}
+
 impl<T, const ROWS: usize, const COLS: usize> ops::Mul<T> for Tensor<T, ROWS, COLS>
 where
-    T: ops::Add<Output = T> + Default + ops::Mul<Output = T> + Copy,  // Element type must support addition and be copyable
+    T: ops::Add<Output = T> + Default + ops::Mul<Output = T> + Copy,  // Element type must support multiplication and be copyable
 {
-    type Output = Self;  // Result of addition is another Tensor with same dimensions
+    type Output = Self;  // Result of multiplication is another Tensor with same dimensions
 
     fn mul(self, scalar: T) -> Tensor<T, ROWS, COLS> {
         let mut result = self;
@@ -537,9 +724,9 @@ where
 
 impl<T, const ROWS: usize, const COLS: usize> ops::Div<T> for Tensor<T, ROWS, COLS>
 where
-    T: Default + ops::Div<Output = T> + Copy,  // Element type must support addition and be copyable
+    T: Default + ops::Div<Output = T> + Copy,  // Element type must support division and be copyable
 {
-    type Output = Self;  // Result of addition is another Tensor with same dimensions
+    type Output = Self;  // Result of division is another Tensor with same dimensions
 
     fn div(self, scalar: T) -> Tensor<T, ROWS, COLS> {
         let mut result = self;
@@ -554,19 +741,18 @@ where
     }
 }
 
-impl<T, const ROWS: usize, const COLS: usize> ops::Div for Tensor<T, ROWS, COLS>
+impl<T, const ROWS: usize, const COLS: usize> ops::Div<&Tensor<T, ROWS, COLS>> for Tensor<T, ROWS, COLS>
 where
-    T: Default + ops::Div<Output = T> + Copy,  // Element type must support addition and be copyable
+    T: Default + ops::Div<Output = T> + Copy,  // Element type must support division and be copyable
 {
-    type Output = Self;  // Result of addition is another Tensor with same dimensions
+    type Output = Self;  // Result of division is another Tensor with same dimensions
 
-    fn div(self, rhs: Tensor<T, ROWS, COLS>) -> Tensor<T, ROWS, COLS> {
-
-        let mut result = Tensor::<T, ROWS, COLS>::new();
+    fn div(self, rhs: &Tensor<T, ROWS, COLS>) -> Tensor<T, ROWS, COLS> {
+        let mut result = self;
         
         for i in 0..ROWS {
             for j in 0..COLS {
-                result.data[i][j] = self.data[i][j] / rhs.data[i][j];
+                result.data[i][j] = result.data[i][j] / rhs.data[i][j];
             }
         }
         
@@ -574,6 +760,31 @@ where
     }
 }
 
+impl<T, const ROWS: usize, const COLS: usize> ops::Div for Tensor<T, ROWS, COLS>
+where
+    T: Default + ops::Div<Output = T> + Copy,
+{
+    type Output = Self;
+
+    fn div(self, rhs: Tensor<T, ROWS, COLS>) -> Tensor<T, ROWS, COLS> {
+        self / &rhs
+    }
+}
+
+impl<T, const ROWS: usize, const COLS: usize> ops::Index<(usize, usize)> for Tensor<T, ROWS, COLS> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        &self.data[row][col]
+    }
+}
+
+impl<T, const ROWS: usize, const COLS: usize> ops::IndexMut<(usize, usize)> for Tensor<T, ROWS, COLS> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        &mut self.data[row][col]
+    }
+}
+
 impl<T, const ROWS: usize, const COLS: usize> PartialEq for Tensor<T, ROWS, COLS>
 where
     T: PartialEq + std::cmp::PartialEq,  // Element type must support addition and be copyable
@@ -586,17 +797,181 @@ where
 impl<T, B, const ROWS: usize, const COLS: usize> TensorConvert<B, ROWS, COLS> for Tensor<T, ROWS, COLS>
 where
     B: From<T> + Default + Copy,
-    T: Sized,
+    T: Copy,
 {
     fn convert(self) -> Tensor<B, ROWS, COLS> {
-        let mut new_data = [[B::default(); COLS]; ROWS];
+        let mut res: Tensor<B, ROWS, COLS> = Tensor::new();
         
-        for (row_idx, row) in self.data.into_iter().enumerate() {
-            for (col_idx, value) in row.into_iter().enumerate() {
-                new_data[row_idx][col_idx] = B::from(value);
+        for (row_idx, row) in self.data.iter().enumerate() {
+            for (col_idx, value) in row.iter().enumerate() {
+                res.data[row_idx][col_idx] = B::from(*value);
             }
         }
         
-        Tensor { data: new_data }
+        res
+    }
+}
+
+// Serialized as a list of rows, e.g. [[1.0, 2.0], [3.0, 4.0]]
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize, const ROWS: usize, const COLS: usize> serde::Serialize for Tensor<T, ROWS, COLS> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.data.iter().map(|row| row.as_slice()))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T, const ROWS: usize, const COLS: usize> serde::Deserialize<'de> for Tensor<T, ROWS, COLS>
+where
+    T: serde::Deserialize<'de> + Default + Copy,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+        let rows: Vec<Vec<T>> = Vec::deserialize(deserializer)?;
+        if rows.len() != ROWS {
+            return Err(D::Error::custom(format!("expected a {}x{} tensor but found {} rows", ROWS, COLS, rows.len())))
+        }
+        let mut res: Tensor<T, ROWS, COLS> = Tensor::new();
+        for (i, (dst, src)) in res.data.iter_mut().zip(rows.iter()).enumerate() {
+            if src.len() != COLS {
+                return Err(D::Error::custom(format!("expected a {}x{} tensor but row {} has {} values", ROWS, COLS, i, src.len())))
+            }
+            dst.copy_from_slice(src);
+        }
+        Ok(res)
+    }
+}
+
+// Element-wise comparison up to float rounding, shared by the tests of every module
+#[cfg(test)]
+pub(crate) fn assert_close<const ROWS: usize, const COLS: usize>(actual: &Tensor<f32, ROWS, COLS>, expected: [[f32; COLS]; ROWS]) {
+    for i in 0..ROWS {
+        for j in 0..COLS {
+            assert!((actual[(i, j)] - expected[i][j]).abs() < 1e-5, "{:?} != {:?}", actual, expected);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Tensor<f32, 2, 3> {
+        Tensor::from_data([[1.0, 2.0, 3.0], [4.0, 0.0, -2.0]])
+    }
+
+    #[test]
+    fn max_axis() {
+        assert_close(&sample().max_axis(Axis::Row).unwrap_row(), [[3.0], [4.0]]);
+        assert_close(&sample().max_axis(Axis::Col).unwrap_col(), [[4.0, 2.0, 3.0]]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn min_axis() {
+        assert_close(&sample().min_axis(Axis::Row).unwrap_row(), [[1.0], [-2.0]]);
+        assert_close(&sample().min_axis(Axis::Col).unwrap_col(), [[1.0, 0.0, -2.0]]);
+    }
+
+    #[test]
+    fn mean_axis() {
+        assert_close(&sample().mean_axis(Axis::Row).unwrap_row(), [[2.0], [2.0 / 3.0]]);
+        assert_close(&sample().mean_axis(Axis::Col).unwrap_col(), [[2.5, 1.0, 0.5]]);
+    }
+
+    #[test]
+    fn var_axis() {
+        assert_close(&sample().var_axis(Axis::Row).unwrap_row(), [[2.0 / 3.0], [56.0 / 9.0]]);
+        assert_close(&sample().var_axis(Axis::Col).unwrap_col(), [[2.25, 1.0, 6.25]]);
+    }
+
+    #[test]
+    fn std_axis() {
+        assert_close(&sample().std_axis(Axis::Row).unwrap_row(), [[(2.0f32 / 3.0).sqrt()], [(56.0f32 / 9.0).sqrt()]]);
+        assert_close(&sample().std_axis(Axis::Col).unwrap_col(), [[1.5, 1.0, 2.5]]);
+    }
+
+    #[test]
+    fn prod_axis() {
+        assert_close(&sample().prod_axis(Axis::Row).unwrap_row(), [[6.0], [0.0]]);
+        assert_close(&sample().prod_axis(Axis::Col).unwrap_col(), [[4.0, 0.0, -6.0]]);
+    }
+
+    #[test]
+    fn argmax_argmin() {
+        assert_eq!(sample().argmax(Axis::Row).unwrap().unwrap_row(), Tensor::from_data([[2], [0]]));
+        assert_eq!(sample().argmax(Axis::Col).unwrap().unwrap_col(), Tensor::from_data([[1, 0, 0]]));
+        assert_eq!(sample().argmin(Axis::Row).unwrap().unwrap_row(), Tensor::from_data([[0], [2]]));
+        assert_eq!(sample().argmin(Axis::Col).unwrap().unwrap_col(), Tensor::from_data([[0, 1, 1]]));
+    }
+
+    #[test]
+    fn argmax_col_of_wide_tensor() {
+        // Regression test: the column indices used to be written as rows of the 1xCOLS result
+        let t: Tensor<i32, 2, 4> = Tensor::from_data([[0, 5, 1, 7], [3, 2, 9, 7]]);
+        assert_eq!(t.argmax(Axis::Col).unwrap().unwrap_col(), Tensor::from_data([[1, 0, 1, 0]]));
+        assert_eq!(t.argmin(Axis::Col).unwrap().unwrap_col(), Tensor::from_data([[0, 1, 0, 0]]));
+    }
+
+    #[test]
+    fn reductions_of_empty_axes() {
+        let no_rows: Tensor<f32, 0, 3> = Tensor::new();
+        assert_eq!(no_rows.max_axis(Axis::Col).unwrap_col(), Tensor::new());
+        assert_eq!(no_rows.prod_axis(Axis::Row).unwrap_row(), Tensor::new());
+        let no_cols: Tensor<i32, 2, 0> = Tensor::new();
+        assert_eq!(no_cols.min_axis(Axis::Row).unwrap_row(), Tensor::new());
+        assert!(no_cols.argmax(Axis::Row).is_none());
+        assert_eq!(no_cols.mean_axis(Axis::Row).unwrap_row(), Tensor::new());
+        assert_eq!(no_cols.var_axis(Axis::Row).unwrap_row(), Tensor::new());
+        assert_eq!(no_rows.mean_axis(Axis::Col).unwrap_col(), Tensor::new());
+        assert_eq!(no_rows.var_axis(Axis::Col).unwrap_col(), Tensor::new());
+    }
+
+    #[test]
+    fn seeded_fills_are_reproducible() {
+        use rand::SeedableRng;
+        use rand::rngs::StdRng;
+
+        let a: Tensor<f32, 4, 5> = Tensor::rand_fill_seeded(42);
+        assert_eq!(a, Tensor::rand_fill_seeded(42));
+        assert_ne!(a, Tensor::rand_fill_seeded(43));
+        assert_eq!(a, Tensor::rand_fill_with(&mut StdRng::seed_from_u64(42)));
+        let mut rng = StdRng::seed_from_u64(42);
+        let first: Tensor<f32, 4, 5> = Tensor::rand_fill_with(&mut rng);
+        assert_ne!(first, Tensor::rand_fill_with(&mut rng));
+    }
+
+    #[test]
+    fn large_tensors_stay_on_the_heap() {
+        // 4 MiB each, more than the stack of a test thread
+        let mut a: Tensor<f32, 1024, 1024> = Tensor::new();
+        for i in 0..1024 {
+            a[(i, i)] = 2.0;
+            a[(i, (i + 1) % 1024)] = 1.0;
+        }
+        let b: Tensor<f32, 1024, 1024> = Tensor::fill(0.5);
+        let c = a - &b + b;
+        let x: Tensor<f32, 1024, 2> = Tensor::fill(1.0);
+        let y = &c * &x;
+        assert_eq!(y, Tensor::fill(3.0));
+    }
+
+    #[test]
+    fn integer_reductions() {
+        let t: Tensor<i32, 2, 2> = Tensor::from_data([[3, -1], [2, 5]]);
+        assert_eq!(t.max_axis(Axis::Col).unwrap_col(), Tensor::from_data([[3, 5]]));
+        assert_eq!(t.min_axis(Axis::Row).unwrap_row(), Tensor::from_data([[-1], [2]]));
+        assert_eq!(t.prod_axis(Axis::Row).unwrap_row(), Tensor::from_data([[-3], [10]]));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_checks_shape() {
+        let json = serde_json::to_string(&sample()).unwrap();
+        assert_eq!(json, "[[1.0,2.0,3.0],[4.0,0.0,-2.0]]");
+        assert_eq!(serde_json::from_str::<Tensor<f32, 2, 3>>(&json).unwrap(), sample());
+        let err = serde_json::from_str::<Tensor<f32, 3, 3>>(&json).unwrap_err();
+        assert!(err.to_string().starts_with("expected a 3x3 tensor but found 2 rows"));
+        let err = serde_json::from_str::<Tensor<f32, 2, 3>>("[[1.0,2.0,3.0],[4.0]]").unwrap_err();
+        assert!(err.to_string().starts_with("expected a 2x3 tensor but row 1 has 1 values"));
+    }
+}
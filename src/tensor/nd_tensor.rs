@@ -0,0 +1,328 @@
+use std::ops;
+
+use num_traits::FromPrimitive;
+
+use super::{DynTensor, ShapeError, Tensor};
+
+// Tensor of any rank, e.g. image batches (N, C, H, W) or sequence batches (N, T, D).
+// The rank is part of the type while the dimensions are only known at runtime.
+// Data is stored contiguously in C order (last axis varies fastest)
+#[derive(Debug, Clone, PartialEq)]
+pub struct NdTensor<T, const RANK: usize> {
+    shape: [usize; RANK],
+    data: Vec<T>
+}
+
+pub type Tensor3<T> = NdTensor<T, 3>;
+pub type Tensor4<T> = NdTensor<T, 4>;
+
+// Offset of one step along every axis in the flat data
+fn strides<const RANK: usize>(shape: &[usize; RANK]) -> [usize; RANK] {
+    let mut strides = [1; RANK];
+    for axis in (0..RANK.saturating_sub(1)).rev() {
+        strides[axis] = strides[axis + 1] * shape[axis + 1];
+    }
+    strides
+}
+
+// Multi-index of the element at `offset` in the flat data
+fn unravel<const RANK: usize>(mut offset: usize, shape: &[usize; RANK]) -> [usize; RANK] {
+    let mut idx = [0; RANK];
+    for axis in (0..RANK).rev() {
+        idx[axis] = offset % shape[axis];
+        offset /= shape[axis];
+    }
+    idx
+}
+
+impl<T, const RANK: usize> NdTensor<T, RANK> {
+    pub fn new(shape: [usize; RANK]) -> Self
+    where
+        T: Default + Clone,
+    {
+        NdTensor::fill(shape, T::default())
+    }
+
+    pub fn fill(shape: [usize; RANK], val: T) -> Self
+    where
+        T: Clone,
+    {
+        NdTensor { shape, data: vec![val; shape.iter().product()] }
+    }
+
+    // `data` holds the elements in C order and must have exactly shape.iter().product() elements
+    pub fn from_vec(shape: [usize; RANK], data: Vec<T>) -> Result<Self, ShapeError> {
+        if data.len() != shape.iter().product::<usize>() {
+            return Err(ShapeError::new("from_vec", &shape, &[data.len()]))
+        }
+        Ok(NdTensor { shape, data })
+    }
+
+    pub fn shape(&self) -> [usize; RANK] {
+        self.shape
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn data(&self) -> &[T] {
+        &self.data
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.data
+    }
+
+    fn offset(&self, idx: &[usize; RANK]) -> usize {
+        let strides = strides(&self.shape);
+        let mut offset = 0;
+        for axis in 0..RANK {
+            assert!(idx[axis] < self.shape[axis], "Index {:?} out of bounds for shape {:?}", idx, self.shape);
+            offset += idx[axis] * strides[axis];
+        }
+        offset
+    }
+
+    // Same elements in the same order under a new shape, possibly of another rank
+    pub fn reshape<const NEW_RANK: usize>(self, shape: [usize; NEW_RANK]) -> Result<NdTensor<T, NEW_RANK>, ShapeError> {
+        if shape.iter().product::<usize>() != self.data.len() {
+            return Err(ShapeError::new("reshape", &self.shape, &shape))
+        }
+        Ok(NdTensor { shape, data: self.data })
+    }
+
+    // Keeps the first axis and flattens all others, e.g. (N, C, H, W) -> (N, C * H * W)
+    // which is what a DenseLayer takes as input
+    pub fn flatten_batch(self) -> NdTensor<T, 2> {
+        let batch = if RANK == 0 { 1 } else { self.shape[0] };
+        let features = self.shape.iter().skip(1).product();
+        NdTensor { shape: [batch, features], data: self.data }
+    }
+
+    // Axis `i` of the result is axis `axes[i]` of self
+    pub fn permute(&self, axes: [usize; RANK]) -> Result<NdTensor<T, RANK>, ShapeError>
+    where
+        T: Copy,
+    {
+        let mut seen = [false; RANK];
+        for &axis in axes.iter() {
+            if axis >= RANK || seen[axis] {
+                return Err(ShapeError::InvalidPermutation { axes: axes.to_vec() })
+            }
+            seen[axis] = true;
+        }
+        let mut shape = [0; RANK];
+        for i in 0..RANK {
+            shape[i] = self.shape[axes[i]];
+        }
+        let mut data = Vec::with_capacity(self.data.len());
+        for offset in 0..self.data.len() {
+            let idx = unravel(offset, &shape);
+            let mut src_idx = [0; RANK];
+            for i in 0..RANK {
+                src_idx[axes[i]] = idx[i];
+            }
+            data.push(self.data[self.offset(&src_idx)]);
+        }
+        Ok(NdTensor { shape, data })
+    }
+
+    // Swaps two axes, the N-dimensional counterpart of Tensor::transpose
+    pub fn transpose(&self, a: usize, b: usize) -> Result<NdTensor<T, RANK>, ShapeError>
+    where
+        T: Copy,
+    {
+        let mut axes = [0; RANK];
+        for (i, axis) in axes.iter_mut().enumerate() {
+            *axis = i;
+        }
+        if let Some(axis) = [a, b].into_iter().find(|&axis| axis >= RANK) {
+            return Err(ShapeError::InvalidAxis { op: "transpose", axis, rank: RANK })
+        }
+        axes.swap(a, b);
+        self.permute(axes)
+    }
+
+    pub fn apply<F>(&mut self, func: F)
+    where
+        F: Fn(&T) -> T
+    {
+        for el in self.data.iter_mut() {
+            *el = func(el);
+        }
+    }
+
+    // Folds every lane along `axis` into one value, starting from its first element.
+    // The reduced axis is kept with length 1 so the result has the same rank, an axis of
+    // length 0 reduces to T::default(). Fails if `axis` is out of range, like `transpose`
+    fn reduce_axis<F>(&self, op: &'static str, axis: usize, f: F) -> Result<NdTensor<T, RANK>, ShapeError>
+    where
+        T: Default + Copy,
+        F: Fn(T, T) -> T
+    {
+        if axis >= RANK {
+            return Err(ShapeError::InvalidAxis { op, axis, rank: RANK })
+        }
+        let mut shape = self.shape;
+        shape[axis] = 1;
+        let count = shape.iter().product();
+        let mut data = Vec::with_capacity(count);
+        for offset in 0..count {
+            let mut idx = unravel(offset, &shape);
+            let lane = (0..self.shape[axis]).map(|k| {
+                idx[axis] = k;
+                self.data[self.offset(&idx)]
+            });
+            data.push(lane.reduce(&f).unwrap_or_default());
+        }
+        Ok(NdTensor { shape, data })
+    }
+
+    pub fn sum_axis(&self, axis: usize) -> Result<NdTensor<T, RANK>, ShapeError>
+    where
+        T: Default + Copy + ops::Add<Output = T>,
+    {
+        self.reduce_axis("sum_axis", axis, |acc, el| acc + el)
+    }
+
+    pub fn prod_axis(&self, axis: usize) -> Result<NdTensor<T, RANK>, ShapeError>
+    where
+        T: Default + Copy + ops::Mul<Output = T>,
+    {
+        self.reduce_axis("prod_axis", axis, |acc, el| acc * el)
+    }
+
+    pub fn max_axis(&self, axis: usize) -> Result<NdTensor<T, RANK>, ShapeError>
+    where
+        T: Default + Copy + PartialOrd,
+    {
+        self.reduce_axis("max_axis", axis, |acc, el| if el > acc { el } else { acc })
+    }
+
+    pub fn min_axis(&self, axis: usize) -> Result<NdTensor<T, RANK>, ShapeError>
+    where
+        T: Default + Copy + PartialOrd,
+    {
+        self.reduce_axis("min_axis", axis, |acc, el| if el < acc { el } else { acc })
+    }
+
+    pub fn mean_axis(&self, axis: usize) -> Result<NdTensor<T, RANK>, ShapeError>
+    where
+        T: Default + Copy + ops::Add<Output = T> + ops::Div<Output = T> + FromPrimitive,
+    {
+        let mut res = self.reduce_axis("mean_axis", axis, |acc, el| acc + el)?;
+        // Sums of an empty axis are T::default(), which is also their mean
+        if self.shape[axis] > 0 {
+            let count = T::from_usize(self.shape[axis]).expect("Failed to convert usize to T");
+            res.apply(|el| *el / count);
+        }
+        Ok(res)
+    }
+}
+
+impl<T, const RANK: usize> ops::Index<[usize; RANK]> for NdTensor<T, RANK> {
+    type Output = T;
+
+    fn index(&self, idx: [usize; RANK]) -> &T {
+        &self.data[self.offset(&idx)]
+    }
+}
+
+impl<T, const RANK: usize> ops::IndexMut<[usize; RANK]> for NdTensor<T, RANK> {
+    fn index_mut(&mut self, idx: [usize; RANK]) -> &mut T {
+        let offset = self.offset(&idx);
+        &mut self.data[offset]
+    }
+}
+
+impl<T, const ROWS: usize, const COLS: usize> From<Tensor<T, ROWS, COLS>> for NdTensor<T, 2>
+where
+    T: Copy,
+{
+    fn from(tensor: Tensor<T, ROWS, COLS>) -> Self {
+        NdTensor::from(DynTensor::from(tensor))
+    }
+}
+
+impl<T> From<DynTensor<T>> for NdTensor<T, 2> {
+    fn from(tensor: DynTensor<T>) -> Self {
+        let shape = [tensor.rows(), tensor.cols()];
+        NdTensor { shape, data: tensor.into_vec() }
+    }
+}
+
+impl<T> From<NdTensor<T, 2>> for DynTensor<T> {
+    fn from(tensor: NdTensor<T, 2>) -> Self {
+        DynTensor::from_vec(tensor.shape[0], tensor.shape[1], tensor.data).expect("NdTensor data always matches its shape")
+    }
+}
+
+impl<T, const ROWS: usize, const COLS: usize> TryFrom<NdTensor<T, 2>> for Tensor<T, ROWS, COLS>
+where
+    T: Default + Copy,
+{
+    type Error = ShapeError;
+
+    fn try_from(tensor: NdTensor<T, 2>) -> Result<Self, ShapeError> {
+        Tensor::try_from(DynTensor::from(tensor))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Tensor3<i32> {
+        NdTensor::from_vec([2, 3, 2], (0..12).collect()).unwrap()
+    }
+
+    #[test]
+    fn permute() {
+        let t = sample();
+        let p = t.permute([2, 0, 1]).unwrap();
+        assert_eq!(p.shape(), [2, 2, 3]);
+        for i in 0..2 {
+            for j in 0..3 {
+                for k in 0..2 {
+                    assert_eq!(p[[k, i, j]], t[[i, j, k]]);
+                }
+            }
+        }
+        assert_eq!(t.permute([0, 0, 1]).unwrap_err().to_string(), "permute: [0, 0, 1] is not a permutation of the axes 0..3");
+        assert_eq!(t.transpose(0, 2).unwrap().shape(), [2, 3, 2]);
+    }
+
+    #[test]
+    fn reductions() {
+        let t = sample();
+        assert_eq!(t.sum_axis(1).unwrap(), NdTensor::from_vec([2, 1, 2], vec![6, 9, 24, 27]).unwrap());
+        assert_eq!(t.max_axis(0).unwrap(), NdTensor::from_vec([1, 3, 2], vec![6, 7, 8, 9, 10, 11]).unwrap());
+        assert_eq!(t.min_axis(2).unwrap(), NdTensor::from_vec([2, 3, 1], vec![0, 2, 4, 6, 8, 10]).unwrap());
+        assert_eq!(t.mean_axis(2).unwrap(), NdTensor::from_vec([2, 3, 1], vec![0, 2, 4, 6, 8, 10]).unwrap());
+        assert_eq!(NdTensor::<i32, 2>::new([2, 0]).sum_axis(1).unwrap(), NdTensor::new([2, 1]));
+        assert_eq!(NdTensor::<i32, 2>::new([2, 0]).mean_axis(1).unwrap(), NdTensor::new([2, 1]));
+    }
+
+    #[test]
+    fn reductions_check_the_axis() {
+        let t = sample();
+        assert_eq!(t.sum_axis(3).unwrap_err(), ShapeError::InvalidAxis { op: "sum_axis", axis: 3, rank: 3 });
+        assert_eq!(t.sum_axis(3).unwrap_err().to_string(), "sum_axis: no axis 3 in a tensor of rank 3");
+        assert!(t.mean_axis(3).is_err());
+        assert!(t.max_axis(7).is_err());
+        assert_eq!(t.transpose(0, 3).unwrap_err(), ShapeError::InvalidAxis { op: "transpose", axis: 3, rank: 3 });
+    }
+
+    #[test]
+    fn flatten_into_tensor() {
+        let images: Tensor4<f32> = NdTensor::fill([4, 1, 2, 2], 1.0);
+        let flat: Tensor<f32, 4, 4> = images.flatten_batch().try_into().unwrap();
+        assert_eq!(flat, Tensor::fill(1.0));
+        assert!(sample().reshape([5, 2]).is_err());
+    }
+}
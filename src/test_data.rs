@@ -1,608 +0,0 @@
-use crate::tensor::Tensor;
-
-pub fn test_data() -> Tensor<f32, 300, 2> {
-    Tensor::from_data([
-    [0.0, 0.0],
-    [0.0014922425836296576, 0.009990176031097269],
-    [0.00999189791770077, 0.017558006613652914],
-    [0.02431473839169871, 0.018084997718817265],
-    [0.002630409362683474, 0.04031832619983172],
-    [0.020935562528965224, 0.045961531176773145],
-    [0.037267097984249944, 0.04779391164173325],
-    [0.021746051471677595, 0.06728000515283755],
-    [0.07819215385098005, 0.020394435516335945],
-    [0.06230888478372453, 0.06619717280160761],
-    [0.0019684332757748894, 0.10099091927747579],
-    [0.11071986456372632, 0.00931614745137613],
-    [0.12037651805275465, 0.014208174767956622],
-    [0.10587542473075061, 0.07767581923187443],
-    [0.13545737781838593, 0.040611059903255906],
-    [0.04300835927140413, 0.14528290392004542],
-    [0.13328154605644818, -0.09141013716401566],
-    [0.04806182224774719, 0.16485402119684736],
-    [0.17508322052502387, 0.049027717979274556],
-    [0.09321408010943948, -0.16776206810917246],
-    [0.19503567733076257, -0.05266162352618751],
-    [0.11829674978174941, -0.17607182518178893],
-    [0.18554635906818234, -0.12229171961307876],
-    [0.23228513542944168, 0.004207152913852532],
-    [0.12287391563417857, -0.2089773053986787],
-    [0.11422216475221307, -0.22521611896634056],
-    [0.008931206930961532, -0.26247435563078353],
-    [0.17749005339866952, -0.20706869930965824],
-    [-0.062253511819390775, -0.2758918951940201],
-    [0.03461784273700387, -0.29087656423351055],
-    [-0.136045185075113, -0.2707749474610152],
-    [-0.06728798403000381, -0.3058161971977914],
-    [-0.27320217424326093, -0.17274173430563908],
-    [0.28015986615135124, -0.1806143972920434],
-    [0.21535645639462142, -0.26752335400732796],
-    [-0.149493953211068, -0.3203729141995223],
-    [-0.06875755062257759, -0.35707674832038755],
-    [-0.2101013688561579, -0.3090906652308964],
-    [-0.3396938146424397, -0.17871770254043196],
-    [-0.27153033528894943, -0.28541114749639285],
-    [-0.28366958090916206, -0.28771551394381456],
-    [-0.3980575700644395, 0.11429471475726335],
-    [-0.3998606793675561, -0.1417500321792047],
-    [-0.3820610476453355, -0.2066000358891497],
-    [-0.40141789176016557, -0.19077353163464503],
-    [-0.43976468976830124, -0.11497211784134316],
-    [-0.4646231867192354, -0.004650964558116437],
-    [-0.3794462559292483, 0.2853168478032443],
-    [-0.42061333068787, -0.24116898496184783],
-    [-0.47737398378923035, 0.13072521620544275],
-    [-0.46650410457894914, 0.19351985185702048],
-    [-0.4222243708743677, 0.2951400755618359],
-    [-0.36401083746139584, 0.378663868747479],
-    [-0.12092041477737332, 0.5215186105074268],
-    [-0.4046820039058645, 0.36572275957582784],
-    [-0.527488940724711, 0.1743484806981818],
-    [-0.3192608423136499, 0.46694738979413886],
-    [-0.46778845316909734, 0.33566463788118023],
-    [-0.45669678017930365, 0.3669582177824593],
-    [0.03356924501204322, 0.5950133996857939],
-    [0.27147514850546783, 0.5418585626918553],
-    [0.47942357650025286, 0.387050605898765],
-    [-0.03517578214485014, 0.6252739730740917],
-    [0.041339598027582185, 0.6350194605843733],
-    [0.20732663598061613, 0.6123170789236746],
-    [0.3178102566053349, 0.5745216289905393],
-    [0.37080226499241914, 0.5540307976285581],
-    [0.05544613397690413, 0.6744925607777552],
-    [0.33774272484590423, 0.5980956819894105],
-    [0.17736649507358018, 0.6740236530859618],
-    [0.31505797275130476, 0.6329987824658969],
-    [0.7060138961912961, 0.12601448446831603],
-    [0.6573566231620233, 0.31113966288426254],
-    [0.6188683400130215, 0.4009014920127396],
-    [0.5589745929425333, 0.4962518539584178],
-    [0.6676627897299435, 0.3579768535485392],
-    [0.5244681566897071, 0.560589664771971],
-    [0.6867671299825968, -0.36508790829114746],
-    [0.5972422589421872, 0.5138624996270711],
-    [0.7562513056343964, 0.25466786352060616],
-    [0.7628508288755804, 0.2665580711071638],
-    [0.7168659920232567, 0.39436612060852516],
-    [0.11679960381483845, -0.8200062781326117],
-    [0.8116879218115689, 0.20988134278309403],
-    [0.34530764766698224, -0.7750413966821718],
-    [0.8460062300790997, -0.14643474734831702],
-    [0.7573326244008045, -0.4255163590829231],
-    [-0.10248911669664948, -0.8727909926570281],
-    [0.44297926660974185, -0.7706444226386244],
-    [0.23548717995985566, -0.8675993467957566],
-    [0.5494310280672261, -0.7242733091787428],
-    [-0.16340403803129175, -0.9045512172689791],
-    [-0.32729402029027116, -0.8697493735071415],
-    [-0.08413366999735074, -0.9356187786394848],
-    [0.8250438960343109, -0.4699396011541719],
-    [-0.832125754412182, -0.4779028505008603],
-    [-0.06738048590031027, -0.9673531326042852],
-    [0.1766629303366112, -0.9637397419745044],
-    [-0.22769713730952715, -0.9633556061310314],
-    [0.14965735265724456, -0.9887379211882313],
-    [-0.0, -0.0],
-    [-0.0028214697053970516, -0.009698954261271407],
-    [-0.0025831743900823286, -0.02003618801851428],
-    [-0.017938651030345996, -0.024422908196155498],
-    [-0.033040309101826704, -0.023255632767720535],
-    [-0.02927150305126951, -0.04115749306793628],
-    [-0.057183178947421885, -0.020079308445576968],
-    [-0.051617190685610714, -0.04832344641786221],
-    [-0.07825517246394172, -0.02015127555575719],
-    [-0.0836325107847486, -0.03563798464779209],
-    [-0.0985136114901079, 0.022318352476087742],
-    [-0.06954264910892226, 0.08665736533762722],
-    [-0.1079867280091797, 0.055056742571763274],
-    [-0.11012875666905182, 0.07151779785332071],
-    [-0.04138655813563505, 0.1352224544873539],
-    [-0.15147294925068747, -0.0035758612889736086],
-    [0.020819232647025617, 0.16026959551808415],
-    [-0.16548559033580149, 0.04584000931234568],
-    [-0.17875099162558677, 0.03325558949317883],
-    [0.028395722301143213, 0.18980689972156436],
-    [0.05713587038899704, 0.19377217121964385],
-    [-0.03673876708454438, 0.20891546526018573],
-    [-0.0012075303282392082, 0.22221894140664314],
-    [-0.05488317957506987, 0.22574747147386884],
-    [-0.06461171962065973, 0.233655385135094],
-    [-0.19879705328357555, 0.15572005255813973],
-    [0.18757378273838624, 0.18381683777676996],
-    [0.06652905063270709, 0.2644882808578991],
-    [0.16292218808310954, 0.23118866364466714],
-    [0.11740679132108217, 0.26837141428949424],
-    [0.2257921902861694, 0.202102081534089],
-    [0.3126368449283821, 0.017590408084158623],
-    [0.03183633847998067, 0.3216606633307113],
-    [0.16473267026208416, 0.2897831231445934],
-    [0.24851961256642624, 0.23703407016716393],
-    [0.3107685979127756, 0.1685530324577254],
-    [0.17277375479455506, 0.319969740151891],
-    [0.3290474457332709, 0.17722133896492137],
-    [0.3606282936137513, -0.1314501378960725],
-    [0.3932864689597057, 0.022671555538680464],
-    [0.32741630011098244, -0.2367429291843118],
-    [0.37282395800894613, -0.18032029070959793],
-    [0.34588336659021, 0.2456549027463353],
-    [0.428275406195779, -0.07234912166080398],
-    [0.3663846504921394, 0.25158130312343113],
-    [0.40177451357343114, -0.21257659911374704],
-    [0.453893830199796, 0.09938172877856956],
-    [0.4311661014598039, -0.19869815734187962],
-    [0.4285856514995316, -0.2266989029276355],
-    [0.4381213652296464, -0.23027086632935345],
-    [-0.018099697903054126, -0.5047260777764395],
-    [-0.03024889632096658, -0.5142626642429579],
-    [0.1675188989366751, -0.49782289399263147],
-    [-0.32247295508853213, -0.42733429660161676],
-    [0.031019895711354883, -0.5445717833555844],
-    [0.3163554584789366, -0.4566850109202445],
-    [-0.26682673787632977, -0.49876932767027393],
-    [0.2072648178232239, -0.5371574083403732],
-    [0.10102818481423143, -0.5770819599479466],
-    [-0.3967018806655347, -0.44474201273631975],
-    [-0.17121857135631724, -0.5813722207340585],
-    [-0.20929145086973877, -0.5795275884923293],
-    [-0.14664227286686346, -0.6088521338238064],
-    [-0.03909325739263079, -0.6351617076874078],
-    [-0.4762003167094698, -0.4372068131839451],
-    [-0.6320448821732452, 0.17775749857629158],
-    [-0.5050757905740816, -0.4351354849014396],
-    [-0.6379611381257199, -0.2258762372602634],
-    [-0.5946832571331283, -0.343715604368274],
-    [-0.6567424543542402, 0.23335832349157823],
-    [-0.3977789692711108, -0.5845689663359512],
-    [-0.667077811735578, -0.26332957486978625],
-    [-0.5903762500934807, -0.42471343651957927],
-    [-0.7365653637161591, -0.03451801764580122],
-    [-0.38828818854461095, 0.6387102478817623],
-    [-0.2230703884115782, 0.7239893854749466],
-    [-0.5600217900369203, 0.5250744845395685],
-    [-0.5427966173247757, 0.5570548481305223],
-    [-0.6373132818137813, 0.46323294918765856],
-    [-0.3612050147900325, 0.711549503038553],
-    [-0.2037933145343743, 0.7819609180385075],
-    [-0.46247890970236843, 0.6749331416398328],
-    [-0.10991288230131949, 0.8209577345591049],
-    [-0.4780628854298477, 0.688725880185801],
-    [-0.5356277690384582, 0.658049717835393],
-    [0.21470519828206314, 0.8313070157254033],
-    [-0.8437479464885621, 0.20665497483808978],
-    [0.1431576885688737, 0.867049025204525],
-    [-0.1133225736343969, 0.8816356680029457],
-    [-0.47326260744643905, 0.7643332668926995],
-    [-0.15839727801500203, 0.895185222905949],
-    [0.18627206414487926, 0.900120271089886],
-    [0.5053391990959524, 0.7798830952719099],
-    [0.8830553054670239, 0.32042830845074444],
-    [0.9494130686424023, -0.012469330673053234],
-    [0.740440717144641, 0.6103867217323953],
-    [0.2727246671318833, 0.9305554626013912],
-    [0.6583538685411798, 0.7256543702018652],
-    [0.8928554428033292, 0.4274449303237921],
-    [0.7005637322745903, 0.713589838087326],
-    [0.0, 0.0],
-    [0.009685175792603612, -0.002868409825508397],
-    [0.0198124698412998, -0.003948120949314795],
-    [0.022298971801181643, 0.020519003439652694],
-    [0.04026680754055234, -0.003327264862232524],
-    [0.032217532898073935, -0.0388946101726091],
-    [0.039466950249971273, 0.045994069401954246],
-    [0.07044533934279558, -0.006078158672910473],
-    [0.048506546403879025, -0.06463018551616302],
-    [0.08903061630403215, -0.01838510730024605],
-    [0.09996389854056172, -0.014500327397789883],
-    [0.09450400494685607, -0.05843519539926503],
-    [0.0876533814076015, -0.08372134170302997],
-    [0.11800140979913784, -0.05760907689484017],
-    [0.06935349056099523, -0.12323981799282543],
-    [-0.04091977015319386, -0.14588493256422716],
-    [-0.10405440804158389, -0.12366270198672208],
-    [0.0010747563725015635, -0.17171380830115093],
-    [0.07530118439651377, -0.16549194200368686],
-    [0.020757694652753095, -0.19079332886560443],
-    [0.0680940516957438, -0.19019821804617576],
-    [0.027365639790398983, -0.21034859255681843],
-    [-0.18660533667605098, -0.12066964976082588],
-    [-0.15249942430097566, -0.17526554100845262],
-    [-0.24152025776072078, -0.020915984465543495],
-    [-0.2518860369318451, -0.017956268034080784],
-    [-0.22209893306672243, -0.14015925852993885],
-    [0.0017475289090861004, -0.27272167393144264],
-    [-0.24938172233148667, -0.13341886723614635],
-    [-0.2814830655306734, -0.08108547635372268],
-    [0.0764591057761974, -0.2932257998514823],
-    [-0.27731388904748355, -0.14542429716075803],
-    [-0.27961045709128224, 0.16216388952960303],
-    [-0.3332470433411459, -0.007584142370469546],
-    [-0.3342903378632647, 0.07872177755515262],
-    [-0.3507101683299439, 0.044605201819405506],
-    [-0.3545270794120183, 0.08088235235366337],
-    [-0.34719825504876783, 0.1383220742296766],
-    [-0.3561259023637018, 0.14320002295147047],
-    [-0.2851212275869854, 0.2718347506788407],
-    [-0.3498234792519712, 0.2021686955519539],
-    [-0.41224127109437847, -0.039626320969041756],
-    [-0.24618484743813782, -0.34550637536657686],
-    [-0.3901189859158437, 0.19094867316963218],
-    [-0.11045625814114804, 0.4305000339546874],
-    [-0.2809117260706772, 0.35735160892869466],
-    [-0.45484219218160465, -0.09494691843283067],
-    [0.1257752894930025, 0.45778350923996336],
-    [-0.4645867894995199, 0.1386981192460815],
-    [-0.29863981347091717, 0.3947014876597452],
-    [0.24388688302571881, 0.44226146219150686],
-    [-0.1302008591215184, 0.4984263434519892],
-    [0.46795538331489017, 0.23855392369602504],
-    [0.3361984408533058, 0.41662215037529543],
-    [0.29213841923378897, 0.4606254499749312],
-    [0.09424610491348133, 0.5475031022900958],
-    [-0.03282471828981878, 0.564703362961099],
-    [0.4590418509338642, 0.3475303801589908],
-    [0.2951303666853204, 0.5060912460064987],
-    [0.5047781067757651, 0.31680735934665283],
-    [0.43491061580927026, 0.422092661005792],
-    [0.26470499284027826, 0.5564048921390996],
-    [0.470424769522424, 0.41340707936988236],
-    [0.6354943021161008, -0.033251611448478366],
-    [0.45487011701393304, 0.4593579386234566],
-    [0.5479689523712907, -0.36167456175213897],
-    [0.6665541675924693, -0.012246881625737211],
-    [0.607952658696056, -0.2973349173943677],
-    [0.6465783144369888, -0.23178670432217824],
-    [0.6952307744547668, -0.04920293431347822],
-    [0.6994592682720001, -0.10346843395871082],
-    [0.7012948195592548, -0.15006947714436097],
-    [0.6293438020731741, -0.36448868106814003],
-    [0.7203089228527155, -0.15771836997405922],
-    [-0.09606694467353673, -0.7412756843803318],
-    [0.21013972954706334, -0.7278477330680987],
-    [0.056411890382020394, -0.7656012789005625],
-    [0.7045720049014973, -0.32941852029602525],
-    [0.2113947211402595, -0.7589896285611418],
-    [0.11142848460994834, -0.7901616611816912],
-    [0.45441150094636523, -0.6682101317670986],
-    [-0.7973256442492914, -0.1835573605872515],
-    [0.42754516842386975, -0.7094064931938573],
-    [0.0480736908867381, -0.8370044090133244],
-    [-0.8270951560953371, -0.18931492511681747],
-    [-0.12074836748559287, -0.8500526503183085],
-    [-0.3825658896631424, -0.7799103896571999],
-    [-0.4690271391462902, -0.7431565640218394],
-    [0.00877108974077699, -0.8888456135768926],
-    [-0.6923057775857274, -0.5734941576051934],
-    [-0.5922958959748166, -0.6896606793221758],
-    [-0.8821602778705896, -0.25827703818769504],
-    [-0.9208968295372735, 0.1246369840056763],
-    [-0.7577408770977843, -0.5552384501681642],
-    [-0.9476811669058155, -0.05866059161352583],
-    [-0.9552006147381435, 0.09173980203141564],
-    [-0.9600301384357514, -0.13658091497174069],
-    [-0.9795356649243111, 0.02267073791234502],
-    [-0.7973916444090792, -0.5865718844520467],
-    [-0.05751158339121666, 0.9983448391091302]])
-}
-
-pub fn test_targets() -> Tensor<usize, 300, 3> {
-    Tensor::from_data([[1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [1, 0, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 1, 0],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1],
-    [0, 0, 1]])
-}
\ No newline at end of file
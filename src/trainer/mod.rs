@@ -0,0 +1,210 @@
+use crate::activator::softmax_cross_entropy::SoftmaxCrossEntropy;
+use crate::dataloader::DataLoader;
+use crate::metrics::Targets;
+use crate::metrics::accuracy::Accuracy;
+use crate::metrics::loss::Loss;
+use crate::model::Module;
+use crate::optimizer::Optimizer;
+use crate::tensor::Tensor;
+
+// What the trainer minimizes: the loss of a batch of model outputs and its gradient w.r.t. those outputs
+pub trait Criterion<const N_OUTPUTS: usize> {
+    fn loss_and_grad<const BATCH_SIZE: usize>(&mut self, outputs: &Tensor<f32, BATCH_SIZE, N_OUTPUTS>, targets: &Targets<BATCH_SIZE, N_OUTPUTS>) -> (f32, Tensor<f32, BATCH_SIZE, N_OUTPUTS>);
+}
+
+impl<L: Loss<N_OUTPUTS>, const N_OUTPUTS: usize> Criterion<N_OUTPUTS> for L {
+    fn loss_and_grad<const BATCH_SIZE: usize>(&mut self, outputs: &Tensor<f32, BATCH_SIZE, N_OUTPUTS>, targets: &Targets<BATCH_SIZE, N_OUTPUTS>) -> (f32, Tensor<f32, BATCH_SIZE, N_OUTPUTS>) {
+        (self.forward(outputs, targets), self.backward(outputs, targets))
+    }
+}
+
+// The model outputs logits, the softmax is part of the criterion
+impl<const N_OUTPUTS: usize> Criterion<N_OUTPUTS> for SoftmaxCrossEntropy<N_OUTPUTS> {
+    fn loss_and_grad<const BATCH_SIZE: usize>(&mut self, outputs: &Tensor<f32, BATCH_SIZE, N_OUTPUTS>, targets: &Targets<BATCH_SIZE, N_OUTPUTS>) -> (f32, Tensor<f32, BATCH_SIZE, N_OUTPUTS>) {
+        let loss = self.forward(outputs, targets);
+        (loss, self.backward(targets).expect("forward just ran with this batch size"))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct BatchStats {
+    pub epoch: usize,
+    pub batch: usize,
+    pub loss: f32,
+    pub accuracy: f32
+}
+
+// Loss and accuracy averaged over all batches of the epoch
+#[derive(Debug, Clone, Copy)]
+pub struct EpochStats {
+    pub epoch: usize,
+    pub loss: f32,
+    pub accuracy: f32
+}
+
+// Hooks called by the trainer, e.g. for custom logging or early inspection of the model
+pub trait Callback {
+    fn on_batch_end(&mut self, _stats: &BatchStats) {}
+    fn on_epoch_end(&mut self, _stats: &EpochStats) {}
+}
+
+// Any closure taking the epoch stats is an epoch end callback
+impl<F: FnMut(&EpochStats)> Callback for F {
+    fn on_epoch_end(&mut self, stats: &EpochStats) {
+        self(stats)
+    }
+}
+
+// Prints loss and accuracy every `every` epochs
+pub struct ProgressPrinter {
+    every: usize
+}
+
+impl ProgressPrinter {
+    pub fn new(every: usize) -> Self {
+        ProgressPrinter { every: every.max(1) }
+    }
+}
+
+impl Callback for ProgressPrinter {
+    fn on_epoch_end(&mut self, stats: &EpochStats) {
+        if stats.epoch.is_multiple_of(self.every) {
+            println!("epoch: {}, loss: {:.3}, acc: {:.3}", stats.epoch, stats.loss, stats.accuracy);
+        }
+    }
+}
+
+pub struct Trainer<M, L, O> {
+    model: M,
+    loss: L,
+    optimizer: O,
+    callbacks: Vec<Box<dyn Callback>>
+}
+
+impl<M, L, O: Optimizer> Trainer<M, L, O> {
+    pub fn new(model: M, loss: L, optimizer: O) -> Self {
+        Trainer { model, loss, optimizer, callbacks: Vec::new() }
+    }
+
+    pub fn add_callback<C: Callback + 'static>(&mut self, callback: C) {
+        self.callbacks.push(Box::new(callback));
+    }
+
+    pub fn model(&self) -> &M {
+        &self.model
+    }
+
+    pub fn model_mut(&mut self) -> &mut M {
+        &mut self.model
+    }
+
+    pub fn into_model(self) -> M {
+        self.model
+    }
+
+    // Runs `epochs` passes over the dataset in mini-batches of BATCH_SIZE samples taken in order.
+    // A last batch with fewer than BATCH_SIZE samples is skipped. Returns the stats of every epoch
+    pub fn fit<const N_SAMPLES: usize, const BATCH_SIZE: usize, const N_INPUTS: usize, const N_OUTPUTS: usize>(
+        &mut self,
+        features: &Tensor<f32, N_SAMPLES, N_INPUTS>,
+        targets: &Targets<N_SAMPLES, N_OUTPUTS>,
+        epochs: usize
+    ) -> Vec<EpochStats>
+    where
+        M: Module<N_INPUTS, N_OUTPUTS>,
+        L: Criterion<N_OUTPUTS>
+    {
+        let mut loader = DataLoader::new(features, targets);
+        self.fit_loader::<N_SAMPLES, BATCH_SIZE, N_INPUTS, N_OUTPUTS>(&mut loader, epochs)
+    }
+
+    // Same as `fit` with the batching, shuffling and last batch handling of `loader`
+    pub fn fit_loader<const N_SAMPLES: usize, const BATCH_SIZE: usize, const N_INPUTS: usize, const N_OUTPUTS: usize>(
+        &mut self,
+        loader: &mut DataLoader<N_SAMPLES, N_INPUTS, N_OUTPUTS>,
+        epochs: usize
+    ) -> Vec<EpochStats>
+    where
+        M: Module<N_INPUTS, N_OUTPUTS>,
+        L: Criterion<N_OUTPUTS>
+    {
+        let mut history = Vec::with_capacity(epochs);
+        for epoch in 0..epochs {
+            let mut epoch_loss = 0.0;
+            let mut epoch_accuracy = 0.0;
+            let mut n_batches = 0;
+            for (batch, (batch_features, batch_targets)) in loader.batches::<BATCH_SIZE>().enumerate() {
+                let stats = self.train_batch(epoch, batch, &batch_features, &batch_targets);
+                epoch_loss += stats.loss;
+                epoch_accuracy += stats.accuracy;
+                n_batches += 1;
+            }
+            let stats = EpochStats {
+                epoch,
+                loss: epoch_loss / n_batches as f32,
+                accuracy: epoch_accuracy / n_batches as f32
+            };
+            for callback in self.callbacks.iter_mut() {
+                callback.on_epoch_end(&stats);
+            }
+            history.push(stats);
+        }
+        history
+    }
+
+    // One forward pass, backward pass and optimizer step
+    fn train_batch<const BATCH_SIZE: usize, const N_INPUTS: usize, const N_OUTPUTS: usize>(
+        &mut self,
+        epoch: usize,
+        batch: usize,
+        features: &Tensor<f32, BATCH_SIZE, N_INPUTS>,
+        targets: &Targets<BATCH_SIZE, N_OUTPUTS>
+    ) -> BatchStats
+    where
+        M: Module<N_INPUTS, N_OUTPUTS>,
+        L: Criterion<N_OUTPUTS>
+    {
+        let outputs = self.model.forward(features);
+        let (loss, dvalues) = self.loss.loss_and_grad(&outputs, targets);
+        // argmax is the same for logits and probabilities so this works whether the softmax is in the model or the criterion
+        let accuracy = Accuracy::<N_OUTPUTS> {}.calculate(outputs, targets.clone()).unwrap_or(0.0);
+        self.model.backward(&dvalues).expect("forward just ran with this batch size");
+        self.model.update_params(&mut self.optimizer);
+        let stats = BatchStats { epoch, batch, loss, accuracy };
+        for callback in self.callbacks.iter_mut() {
+            callback.on_batch_end(&stats);
+        }
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    use super::*;
+    use crate::activator::relu::ReLU;
+    use crate::layer::DenseLayer;
+    use crate::model::Sequential;
+    use crate::optimizer::adam::Adam;
+    use crate::datasets;
+
+    #[test]
+    fn fit_reduces_loss_and_calls_callbacks() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let model = Sequential::new(DenseLayer::<2, 16>::new_with_rng(&mut rng), ReLU::new()).then(DenseLayer::<16, 3>::new_with_rng(&mut rng));
+        let mut trainer = Trainer::new(model, SoftmaxCrossEntropy::new(), Adam::new(0.05, 0.0, 1e-7, 0.9, 0.999));
+        let epochs_seen = Rc::new(Cell::new(0));
+        let counter = epochs_seen.clone();
+        trainer.add_callback(move |_: &EpochStats| counter.set(counter.get() + 1));
+        let (features, targets) = datasets::spiral::<300, 3>(0.2, 0);
+        let history = trainer.fit::<300, 300, 2, 3>(&features, &targets, 50);
+        assert_eq!(history.len(), 50);
+        assert_eq!(epochs_seen.get(), 50);
+        assert!(history[49].loss < history[0].loss);
+    }
+}